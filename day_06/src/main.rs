@@ -1,12 +1,23 @@
 const PUZZLE_INPUT: &str = include_str!("../puzzle_input.txt");
 
-use std::collections::{HashSet, HashMap};
-use grid::GridVec;
+use std::collections::HashSet;
+use grid::{Grid, GridCell, GridMap, GridVec};
+use grid::ray_index::RayIndex;
+
+#[derive(GridCell, Clone, Copy, PartialEq, Eq, Debug)]
+enum Cell {
+    #[cell('.')]
+    Open,
+    #[cell('#')]
+    Obsticle,
+    #[cell('^')]
+    Guard
+}
 
 #[derive(Default)]
 struct State {
     obsticle_locs: HashSet<GridVec>,
-    visited_loc_dirs: HashMap<GridVec, Vec<GridVec>>,
+    visited_loc_dirs: GridMap<Vec<GridVec>>,
     guard_loc: GridVec,
     guard_dir: GridVec,
     row_count: usize,
@@ -34,44 +45,44 @@ fn solve_part_2(input: &str) -> usize {
     let mut orig_state = parse_state(input);
     while matches!(tick(&mut orig_state), TickOutcome::Running) {};
 
-    let mut vialble_locs = 0;
-    for candidate_loc in orig_state.visited_loc_dirs.into_keys() {
-        let mut sim_state = parse_state(input);
-        if candidate_loc == sim_state.guard_loc {
-            continue;
-        }
+    let start = parse_state(input);
+    let mut rays = RayIndex::new(start.row_count, start.col_count, start.obsticle_locs.iter().copied());
+    orig_state.visited_loc_dirs.into_keys()
+        .filter(|&candidate_loc| candidate_loc != start.guard_loc)
+        .filter(|&candidate_loc| {
+            rays.temporary = Some(candidate_loc);
+            guard_loops(&rays, start.guard_loc, start.guard_dir)
+        })
+        .count()
+}
 
-        sim_state.obsticle_locs.insert(candidate_loc);
-
-        let mut continue_sim = true;
-        while continue_sim {
-            let outcome = tick(&mut sim_state);
-            if !matches!(outcome, TickOutcome::Running) {
-                continue_sim = false;
-            }
-            if matches!(outcome, TickOutcome::LoopDetected) {
-                vialble_locs += 1;
-            }
-        };
+/// Jumps the guard from obsticle to obsticle, rather than stepping one cell at a time. The guard
+/// is in a loop if it ever turns at the same spot while facing the same way.
+fn guard_loops(rays: &RayIndex, mut guard_loc: GridVec, mut guard_dir: GridVec) -> bool {
+    let mut turns = HashSet::new();
+    while let Some(obsticle_loc) = rays.next_blocking(guard_loc, guard_dir) {
+        guard_loc = obsticle_loc - guard_dir;
+        if !turns.insert((guard_loc, guard_dir)) {
+            return true;
+        }
+        guard_dir = guard_dir.rot_90_anti();
     }
 
-    vialble_locs
+    false
 }
 
-
 fn parse_state(input: &str) -> State {
     let mut state = State::default();
-
-    for (i, mut line) in input.lines().enumerate() {
-        line = line.trim();
-        state.col_count = line.len();
-        state.row_count = i+1;
-        for (j, c) in line.trim().chars().enumerate() {
-            if c == '#' {
-                state.obsticle_locs.insert(GridVec::from([i, j]));
-            } else if c == '^' {
-                state.guard_loc = GridVec::from([i, j]);
-            }
+    let grid = Grid::<Cell>::parse(input).unwrap();
+    state.row_count = grid.row_count;
+    state.col_count = grid.col_count;
+    state.visited_loc_dirs = GridMap::new(grid.bounds());
+
+    for loc in grid.locs() {
+        match grid[loc] {
+            Cell::Obsticle => { state.obsticle_locs.insert(loc); },
+            Cell::Guard => state.guard_loc = loc,
+            Cell::Open => {}
         }
     }
 
@@ -85,11 +96,7 @@ fn tick(state: &mut State) -> TickOutcome {
     let mut next_loc = state.guard_loc + state.guard_dir;
 
     // If going straight takes us off the map, we're done
-    if next_loc.i < 0 
-        || next_loc.j < 0 
-        || next_loc.i >= state.row_count as i64
-        || next_loc.j >= state.col_count as i64
-    {
+    if !is_on_map(state, next_loc) {
         return TickOutcome::Finished;
     }
 
@@ -99,8 +106,13 @@ fn tick(state: &mut State) -> TickOutcome {
         next_loc = state.guard_loc + state.guard_dir;
     }
 
+    // Turning can point us off the map too
+    if !is_on_map(state, next_loc) {
+        return TickOutcome::Finished;
+    }
+
     // At this point we know where we're going next. Check if we've been there before
-    if let Some(dirs) = state.visited_loc_dirs.get_mut(&next_loc) {
+    if let Some(dirs) = state.visited_loc_dirs.get_mut(next_loc) {
         // ... we've been here. Have we been here walking in the same direction?
         if dirs.contains(&state.guard_dir) {
             return TickOutcome::LoopDetected;
@@ -116,6 +128,13 @@ fn tick(state: &mut State) -> TickOutcome {
     TickOutcome::Running
 }
 
+fn is_on_map(state: &State, loc: GridVec) -> bool {
+    loc.i >= 0
+        && loc.j >= 0
+        && loc.i < state.row_count as i64
+        && loc.j < state.col_count as i64
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,4 +150,12 @@ mod tests {
     fn test_solve_part_2() {
         assert_eq!(solve_part_2(&TEST_INPUT), 6);
     }
+
+    #[test]
+    fn test_turn_off_map() {
+        // The guard walks down column 0 and turns to face west, straight off the map
+        let input = "...\n#..\n^#.\n...\n#..";
+        assert_eq!(solve_part_1(input), 1);
+        assert_eq!(solve_part_2(input), 0);
+    }
 }
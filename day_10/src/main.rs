@@ -1,6 +1,4 @@
-#![feature(let_chains)]
-use std::collections::BTreeSet;
-use grid::{Grid, GridVec};
+use grid::{Grid, GridVec, Neighborhood};
 
 const PUZZLE_INPUT: &str = include_str!("../puzzle_input.txt");
 
@@ -11,32 +9,22 @@ fn main() {
 
 fn solve_part_1(input: &str) -> usize {
     let grid = parse_input(input);
-    let mut total_score = 0;
-    for i in 0..grid.row_count {
-        for j in 0..grid.col_count {
-            let loc = GridVec::from([i, j]);
-            if grid[loc] == 0 {
-                total_score += peaks_reachable_from(loc, &grid).len();
-            }
-        }
-    }
+    let reachable = grid.reachable_targets(&Neighborhood::VonNeumann, is_climb, is_peak).unwrap();
 
-    total_score
+    grid.locs()
+        .filter(|&loc| grid[loc] == 0)
+        .map(|loc| reachable[loc].len())
+        .sum()
 }
 
-fn solve_part_2(input: &str) -> u32 {
+fn solve_part_2(input: &str) -> u64 {
     let grid = parse_input(input);
-    let mut total_rating = 0;
-    for i in 0..grid.row_count {
-        for j in 0..grid.col_count {
-            let loc = GridVec::from([i, j]);
-            if grid[loc] == 0 {
-                total_rating += trail_count_from(loc, &grid);
-            }
-        }
-    }
+    let trail_counts = grid.count_paths(&Neighborhood::VonNeumann, is_climb, is_peak).unwrap();
 
-    total_rating
+    grid.locs()
+        .filter(|&loc| grid[loc] == 0)
+        .map(|loc| trail_counts[loc])
+        .sum()
 }
 
 fn parse_input(input: &str) -> Grid<u8> {
@@ -49,43 +37,13 @@ fn parse_input(input: &str) -> Grid<u8> {
     ).unwrap()
 }
 
-fn peaks_reachable_from(start: GridVec, grid: &Grid<u8>) -> BTreeSet<GridVec> {
-    if let Some(level) = grid.get(start) && *level == 9 {
-        return [start].into_iter().collect()
-    }
-
-    let mut adj_dirs = vec![GridVec::from([1, 0])];
-    for _ in 0..3 {
-        adj_dirs.push(adj_dirs[adj_dirs.len()-1].rot_90());
-    }
-
-    let this_level = grid[start];
-    adj_dirs.into_iter()
-        .map(|dir| start + dir)
-        .filter(|&adj_loc| match grid.get(adj_loc) { Some(&adj_level) => adj_level == this_level + 1, None => false})
-        .map(|loc| peaks_reachable_from(loc, grid))
-        .flatten()
-        .collect()
+/// Trails only ever go up one level at a time
+fn is_climb(&from: &u8, &to: &u8) -> bool {
+    to == from + 1
 }
 
-fn trail_count_from(start: GridVec, grid: &Grid<u8>) -> u32 {
-    if let Some(level) = grid.get(start) && *level == 9 {
-        return 1;
-    }
-
-    let mut adj_dirs = vec![GridVec::from([1, 0])];
-    for _ in 0..3 {
-        adj_dirs.push(adj_dirs.last().unwrap().rot_90());
-    }
-
-    let this_level = grid[start];
-    let res = adj_dirs.into_iter()
-        .map(|dir| start + dir)
-        .filter(|&adj_loc| match grid.get(adj_loc) { Some(&adj_level) => adj_level == this_level + 1, None => false})
-        .map(|loc| trail_count_from(loc, grid))
-        .sum();
-
-    res
+fn is_peak(_: GridVec, &level: &u8) -> bool {
+    level == 9
 }
 
 #[cfg(test)]
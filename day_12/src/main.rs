@@ -1,5 +1,5 @@
 use std::collections::BTreeMap;
-use grid::{Grid, GridVec};
+use grid::{Grid, GridMap, GridVec};
 
 const PUZZLE_INPUT: &str = include_str!("../puzzle_input.txt");
 
@@ -28,12 +28,12 @@ fn main() {
 fn solve(input: &str, part: Part) -> u32 {
     let grid = parse_input(input);
 
-    let mut registry = BTreeMap::new();
+    let mut registry = GridMap::new(grid.bounds());
     let mut current_region_id = 0;
     for i in 0..grid.row_count {
         for j in 0..grid.col_count {
             let loc = GridVec::from([i, j]);
-            if !registry.contains_key(&loc) {
+            if !registry.contains_key(loc) {
                 flood_fill(loc, &grid, current_region_id, &mut registry);
                 current_region_id += 1;
             }
@@ -41,7 +41,7 @@ fn solve(input: &str, part: Part) -> u32 {
     }
 
     let mut region_totals: BTreeMap<u32, [u32; 3]> = BTreeMap::new();
-    let mut corner_locs = BTreeMap::new();
+    let mut corner_locs = GridMap::new(grid.bounds());
     for (loc, RegEntry { region_id, boarder_contrib, corner_contrib }) in registry.into_iter() {
         if let Some([area, perimeter, corners]) = region_totals.get_mut(&region_id) {
             *area += 1;
@@ -79,8 +79,8 @@ fn parse_input(input: &str) -> Grid<char> {
 
 }
 
-fn flood_fill(start: GridVec, grid: &Grid<char>, region_id: u32, registry: &mut BTreeMap<GridVec, RegEntry>) {
-    if registry.contains_key(&start) {
+fn flood_fill(start: GridVec, grid: &Grid<char>, region_id: u32, registry: &mut GridMap<RegEntry>) {
+    if registry.contains_key(start) {
         return;
     }
 
@@ -114,7 +114,7 @@ fn flood_fill(start: GridVec, grid: &Grid<char>, region_id: u32, registry: &mut
     registry.insert(start, RegEntry { region_id, boarder_contrib, corner_contrib });
 
     let unregged_same_color_adj_locs = same_color_adj_locs.into_iter()
-        .filter(|loc| !registry.contains_key(loc))
+        .filter(|&loc| !registry.contains_key(loc))
         .collect::<Vec<_>>();
     
     for loc in unregged_same_color_adj_locs {
@@ -122,11 +122,11 @@ fn flood_fill(start: GridVec, grid: &Grid<char>, region_id: u32, registry: &mut
     }
 }
 
-fn render_with_highlight_levels(grid: &Grid<char>, highlights: &BTreeMap<GridVec, u32>) {
+fn render_with_highlight_levels(grid: &Grid<char>, highlights: &GridMap<u32>) {
     for i in 0..grid.row_count {
         for j in 0..grid.col_count {
             let loc = GridVec::from([i, j]);
-            let level = highlights.get(&loc);
+            let level = highlights.get(loc);
             match level {
                 Some(1) => print!("{}{}", ANSI_BRIGHT_BG, ANSI_BG_RED),
                 Some(2) => print!("{}{}", ANSI_BRIGHT_BG, ANSI_BG_GREEN),
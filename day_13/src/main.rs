@@ -1,7 +1,6 @@
 const PUZZLE_INPUT: &str = include_str!("../puzzle_input.txt");
 
-use grid::GridVec;
-use regex::Regex;
+use grid::{GridVec, Point};
 
 struct ClawMachine {
     a_step: GridVec,
@@ -38,32 +37,12 @@ fn parse_input(input: &str) -> Vec<ClawMachine> {
     // Button A: X+94, Y+34
     // Button B: X+22, Y+67
     // Prize: X=8400, Y=5400
-    let button_pattern = Regex::new(r"X\+(\d+), Y\+(\d+)").unwrap();
-    let prize_pattern = Regex::new(r"X=(\d+), Y=(\d+)").unwrap();
-
     let mut machines = Vec::new();
     let mut lines = input.lines().map(|l| l.trim());
     loop {
-        let a_line = lines.next().unwrap();
-        let caps = button_pattern.captures(a_line).unwrap();
-        let a_step = GridVec {
-            i: caps.get(2).unwrap().as_str().parse().unwrap(),
-            j: caps.get(1).unwrap().as_str().parse().unwrap()
-        };
-
-        let b_line = lines.next().unwrap();
-        let caps = button_pattern.captures(b_line).unwrap();
-        let b_step = GridVec {
-            i: caps.get(2).unwrap().as_str().parse().unwrap(),
-            j: caps.get(1).unwrap().as_str().parse().unwrap()
-        };
-
-        let target_line = lines.next().unwrap();
-        let caps = prize_pattern.captures(target_line).unwrap();
-        let target_loc = GridVec {
-            i: caps.get(2).unwrap().as_str().parse().unwrap(),
-            j: caps.get(1).unwrap().as_str().parse().unwrap()
-        };
+        let a_step = Point::parse_xy(lines.next().unwrap()).unwrap().into();
+        let b_step = Point::parse_xy(lines.next().unwrap()).unwrap().into();
+        let target_loc = Point::parse_xy(lines.next().unwrap()).unwrap().into();
 
         machines.push( ClawMachine {
             a_step,
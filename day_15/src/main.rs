@@ -1,9 +1,25 @@
-use std::collections::BTreeSet;
-
-use grid::{GridVec, Grid};
+use grid::{GridVec, Grid, GridCell};
+use grid::push::{Push, PushEngine, Shape};
 
 const PUZZLE_INPUT: &str = include_str!("../puzzle_input.txt");
 
+#[derive(GridCell, Clone, Copy, PartialEq, Eq, Debug)]
+enum Tile {
+    #[cell('.')]
+    Empty,
+    #[cell('#')]
+    Wall,
+    #[cell('O')]
+    SmallBox,
+    #[cell('[')]
+    BoxLeft,
+    #[cell(']')]
+    BoxRight,
+    #[cell('@')]
+    Bot
+}
+use Tile::*;
+
 enum ParseMode {
     Normal,
     Wide
@@ -18,16 +34,17 @@ fn main() {
 fn solve_part_1(input: &str) -> usize {
     let (mut grid, moves) = parse_input(input, Normal);
     let mut bot_loc = find_bot(&grid);
+    let rules = warehouse_rules();
 
     for dir in moves {
-        bot_loc = do_move(&mut grid, bot_loc, dir);
+        bot_loc = do_move(&mut grid, &rules, bot_loc, dir);
     }
 
     grid.rows.into_iter()
         .enumerate()
         .map(|(i, row)| row.into_iter()
              .enumerate()
-             .filter_map(move |(j, c)| if c == 'O' {Some(100*i + j)} else { None })
+             .filter_map(move |(j, c)| if c == SmallBox {Some(100*i + j)} else { None })
         )
         .flatten()
         .sum()
@@ -36,22 +53,23 @@ fn solve_part_1(input: &str) -> usize {
 fn solve_part_2(input: &str) -> usize {
     let (mut grid, moves) = parse_input(input, Wide);
     let mut bot_loc = find_bot(&grid);
+    let rules = warehouse_rules();
 
     for dir in moves {
-        bot_loc = do_move(&mut grid, bot_loc, dir);
+        bot_loc = do_move(&mut grid, &rules, bot_loc, dir);
     }
 
     grid.rows.into_iter()
         .enumerate()
         .map(|(i, row)| row.into_iter()
              .enumerate()
-             .filter_map(move |(j, c)| if c == '[' {Some(100*i + j)} else { None })
+             .filter_map(move |(j, c)| if c == BoxLeft {Some(100*i + j)} else { None })
         )
         .flatten()
         .sum()
 }
 
-fn parse_input(input: &str, mode: ParseMode) -> (Grid<char>, Vec<GridVec>) {
+fn parse_input(input: &str, mode: ParseMode) -> (Grid<Tile>, Vec<GridVec>) {
     let mut lines = input.lines().map(|l| l.trim());
 
     // First pull out the lines about the grid. We'll process these later.
@@ -77,20 +95,17 @@ fn parse_input(input: &str, mode: ParseMode) -> (Grid<char>, Vec<GridVec>) {
         })
         .collect();
 
+    let grid = Grid::<Tile>::parse(&grid_lines.join("\n")).unwrap();
     (
         match mode {
-            Normal => Grid::from_iter(grid_lines.into_iter().map(|l| l.chars())).unwrap(),
-            Wide => Grid::from_iter(grid_lines.into_iter()
-                .map(|l| l.chars()
-                     .map(|c| match c {
-                         '.' => "..".chars(),
-                         'O' => "[]".chars(),
-                         '#' => "##".chars(),
-                         '@' => "@.".chars(),
-                         _ => panic!("Invalid grid character '{}'", c)
-                     })
-                     .flatten()
-            )).unwrap()
+            Normal => grid,
+            Wide => grid.expand_cells(|&tile| match tile {
+                Empty => [[Empty, Empty]],
+                SmallBox => [[BoxLeft, BoxRight]],
+                Wall => [[Wall, Wall]],
+                Bot => [[Bot, Empty]],
+                BoxLeft | BoxRight => panic!("Wide box in narrow grid")
+            })
         },
         moves
     )
@@ -98,25 +113,30 @@ fn parse_input(input: &str, mode: ParseMode) -> (Grid<char>, Vec<GridVec>) {
 
 /// Bot steps in `bot_dir` from `bot_loc` (if possible) pushing all boxes in its path.
 /// Function returns new `bot_loc`, regardless of whether or not it changed.
-fn do_move(grid: &mut Grid<char>, bot_loc: GridVec, bot_dir: GridVec) -> GridVec {
-    match locs_to_slide(grid, &vec![bot_loc], bot_dir) {
-        None => return bot_loc,
-        Some(slide_locs) => {
-            for loc in slide_locs {
-                grid[loc+bot_dir] = grid[loc];
-                grid[loc] = '.';
-            }
-        }
+fn do_move(grid: &mut Grid<Tile>, rules: &PushEngine<Tile>, bot_loc: GridVec, bot_dir: GridVec) -> GridVec {
+    match rules.push(grid, bot_loc, bot_dir) {
+        Push::Clear(_) => bot_loc + bot_dir,
+        Push::Blocked(_) => bot_loc
     }
+}
 
-    bot_loc + bot_dir
+/// The bot and both kinds of box can be pushed around. Walls block everything.
+fn warehouse_rules() -> PushEngine<Tile> {
+    PushEngine {
+        empty: Empty,
+        shapes: vec![
+            Shape::single(Bot),
+            Shape::single(SmallBox),
+            Shape::from_rows([[BoxLeft, BoxRight]])
+        ]
+    }
 }
 
-/// Finds the location the first '@' on the grid or panics
-fn find_bot(grid: &Grid<char>) -> GridVec {
+/// Finds the location the first bot on the grid or panics
+fn find_bot(grid: &Grid<Tile>) -> GridVec {
     for (i, row) in grid.rows.iter().enumerate() {
         for (j, c) in row.iter().enumerate() {
-            if *c == '@' {
+            if *c == Bot {
                 return GridVec::from([i, j]);
             }
         }
@@ -125,66 +145,29 @@ fn find_bot(grid: &Grid<char>) -> GridVec {
     panic!("Didn't find '@' in grid");
 }
 
-/// Recursively determines all locations which must be slid in direction `dir` to accomdate sliding
-/// `locs`, and returns a `Some(vec![...])` of these locations with more distant locations at the front
-/// of the Vec. If an obstuction is encounterd, returns `None`
-fn locs_to_slide(grid: &Grid<char>, locs: &Vec<GridVec>, dir: GridVec) -> Option<Vec<GridVec>> {
-    if locs.len() == 0 {
-        return Some(vec![]);
-    }
-    
-    let mut new_slide_locs = vec![];
-    for &loc in locs.iter() {
-        match grid[loc] {
-            '#' => return None,
-            'O' | '@' => new_slide_locs.extend(locs_to_slide(grid, &vec![loc + dir], dir)?),
-            '[' => match dir {
-                GridVec {i: 0, j: 1} => {
-                    new_slide_locs.push(loc + dir);
-                    new_slide_locs.extend(locs_to_slide(grid, &vec![loc + 2*dir], dir)?)
-                },
-                GridVec {i: 0, j:-1} => {
-                    new_slide_locs.extend(locs_to_slide(grid, &vec![loc + dir], dir)?)
-                },
-                _ /* vertical */ => { 
-                    new_slide_locs.extend(locs_to_slide(grid, &vec![loc + dir, loc + dir + GridVec::from([0, 1])], dir)?);
-                    new_slide_locs.push(loc + GridVec::from([0, 1]));
-                }
-            },
-            ']' => match dir {
-                GridVec {i: 0, j: 1} => {
-                    new_slide_locs.extend(locs_to_slide(grid, &vec![loc + dir], dir)?)
-                },
-                GridVec {i: 0, j:-1} => {
-                    new_slide_locs.push(loc + dir);
-                    new_slide_locs.extend(locs_to_slide(grid, &vec![loc + 2*dir], dir)?)
-                },
-                _ /* vertical */ => { 
-                    new_slide_locs.extend(locs_to_slide(grid, &vec![ loc + dir, loc + dir + GridVec::from([0,-1]) ], dir)?);
-                    new_slide_locs.push(loc + GridVec::from([0,-1]));
-                }
-            },
-            _ => {}
-        }
-    }
-
-    let mut dedupe = BTreeSet::new();
-
-    new_slide_locs = new_slide_locs.into_iter()
-            .chain(locs.iter().map(|l| *l))
-            .filter(|loc| grid[*loc] != '.')
-            .filter(|loc| dedupe.insert(*loc))
-            .collect();
-    new_slide_locs.sort_by_key(|loc| -dir.i * loc.i - dir.j * loc.j);
-
-    Some(new_slide_locs)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use grid::assert_grid_eq;
 
     const TEST_INPUT: &str = include_str!("../test_input.txt");
+    const TEST_INPUT_2: &str = include_str!("../test_input_2.txt");
+    const TEST_INPUT_3: &str = include_str!("../test_input_3.txt");
+
+    fn run_moves(input: &str, mode: ParseMode) -> Grid<Tile> {
+        let (mut grid, moves) = parse_input(input, mode);
+        let mut bot_loc = find_bot(&grid);
+        let rules = warehouse_rules();
+        for dir in moves {
+            bot_loc = do_move(&mut grid, &rules, bot_loc, dir);
+        }
+        grid
+    }
+
+    fn parse_grid(s: &str) -> Grid<Tile> {
+        let rows = s.lines().map(|l| l.trim()).collect::<Vec<_>>();
+        Grid::parse(&rows.join("\n")).unwrap()
+    }
 
     #[test]
     fn test_solve_part_1() {
@@ -195,4 +178,32 @@ mod tests {
     fn test_solve_part_2() {
         assert_eq!(solve_part_2(TEST_INPUT), 9021);
     }
+
+    #[test]
+    fn test_small_layout() {
+        let expected = parse_grid("\
+            ########
+            #....OO#
+            ##.....#
+            #.....O#
+            #.#O@..#
+            #...O..#
+            #...O..#
+            ########");
+        assert_grid_eq!(run_moves(TEST_INPUT_2, Normal), expected);
+        assert_eq!(solve_part_1(TEST_INPUT_2), 2028);
+    }
+
+    #[test]
+    fn test_small_wide_layout() {
+        let expected = parse_grid("\
+            ##############
+            ##...[].##..##
+            ##...@.[]...##
+            ##....[]....##
+            ##..........##
+            ##..........##
+            ##############");
+        assert_grid_eq!(run_moves(TEST_INPUT_3, Wide), expected);
+    }
 }
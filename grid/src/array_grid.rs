@@ -0,0 +1,159 @@
+use std::collections::BTreeSet;
+use std::ops::{Index, IndexMut};
+
+use crate::{AsChar, Error, Grid, GridVec, Neighborhood, Result, ANSI_BRIGHT_BG, ANSI_RESET};
+
+/// A fixed-size `R` x `C` grid stored inline (on the stack, unless boxed), for small grids such as
+/// templates and kernels where allocating rows on the heap isn't worth it
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ArrayGrid<T, const R: usize, const C: usize> {
+    pub cells: [[T; C]; R]
+}
+
+impl<T, const R: usize, const C: usize> ArrayGrid<T, R, C> {
+    pub const ROW_COUNT: usize = R;
+    pub const COL_COUNT: usize = C;
+
+    pub fn new(cells: [[T; C]; R]) -> Self {
+        ArrayGrid { cells }
+    }
+
+    pub fn get(&self, loc: GridVec) -> Option<&T> {
+        if loc.i < 0
+            || loc.j < 0
+            || loc.i as usize >= R
+            || loc.j as usize >= C
+        {
+            return None
+        }
+
+        Some(&self.cells[loc.i as usize][loc.j as usize])
+    }
+
+    pub fn get_mut(&mut self, loc: GridVec) -> Option<&mut T> {
+        if loc.i < 0
+            || loc.j < 0
+            || loc.i as usize >= R
+            || loc.j as usize >= C
+        {
+            return None
+        }
+
+        Some(&mut self.cells[loc.i as usize][loc.j as usize])
+    }
+
+    /// Iterates over the location of every cell in row-major order
+    pub fn locs(&self) -> impl Iterator<Item = GridVec> + use<T, R, C> {
+        (0..R).flat_map(|i| (0..C).map(move |j| GridVec::from([i, j])))
+    }
+
+    /// Returns the locations adjacent to `loc` (according to `neighborhood`) which are on the grid
+    pub fn neighbors<'a>(&'a self, loc: GridVec, neighborhood: &'a Neighborhood) -> impl Iterator<Item = GridVec> + 'a {
+        neighborhood.offsets()
+            .iter()
+            .map(move |&dir| loc + dir)
+            .filter(|&adj_loc| self.get(adj_loc).is_some())
+    }
+}
+
+impl<T: Copy, const R: usize, const C: usize> ArrayGrid<T, R, C> {
+    /// Creates a grid with every cell set to `fill`
+    pub fn filled(fill: T) -> Self {
+        ArrayGrid { cells: [[fill; C]; R] }
+    }
+}
+
+impl<T: AsChar, const R: usize, const C: usize> ArrayGrid<T, R, C> {
+    pub fn render(&self) {
+        self.render_with_highlights(&BTreeSet::new());
+    }
+
+    pub fn render_with_highlights(&self, highlights: &BTreeSet<GridVec>) {
+        for loc in self.locs() {
+            let hl = highlights.contains(&loc);
+            if hl {
+                print!("{}", ANSI_BRIGHT_BG);
+            }
+            print!("{}", self[loc].as_char());
+            if hl {
+                print!("{}", ANSI_RESET);
+            }
+            if loc.j as usize == C - 1 {
+                println!();
+            }
+        }
+    }
+}
+
+impl<T, const R: usize, const C: usize> Index<GridVec> for ArrayGrid<T, R, C> {
+    type Output = T;
+
+    fn index(&self, index: GridVec) -> &Self::Output {
+        self.get(index).unwrap()
+    }
+}
+
+impl<T, const R: usize, const C: usize> IndexMut<GridVec> for ArrayGrid<T, R, C> {
+    fn index_mut(&mut self, index: GridVec) -> &mut Self::Output {
+        self.get_mut(index).unwrap()
+    }
+}
+
+impl<T: Clone, const R: usize, const C: usize> TryFrom<&Grid<T>> for ArrayGrid<T, R, C> {
+    type Error = Error;
+
+    fn try_from(grid: &Grid<T>) -> Result<Self> {
+        if grid.row_count != R || grid.col_count != C {
+            return Err(Error::WrongDimensions);
+        }
+
+        Ok(ArrayGrid {
+            cells: std::array::from_fn(|i| std::array::from_fn(|j| grid.rows[i][j].clone()))
+        })
+    }
+}
+
+impl<T, const R: usize, const C: usize> From<ArrayGrid<T, R, C>> for Grid<T> {
+    fn from(array_grid: ArrayGrid<T, R, C>) -> Self {
+        Grid {
+            rows: array_grid.cells.into_iter().map(Vec::from).collect(),
+            row_count: R,
+            col_count: C
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kernel_matches_grid() {
+        // X-MAS template from day 4
+        let template = ArrayGrid::new([
+            ['M', '.', 'S'],
+            ['.', 'A', '.'],
+            ['M', '.', 'S'],
+        ]);
+        let grid = Grid::from(template);
+
+        for loc in template.locs() {
+            assert_eq!(template.get(loc), grid.get(loc));
+        }
+        assert_eq!(template.get(GridVec::from([3, 0])), None);
+        assert_eq!(ArrayGrid::<char, 3, 3>::try_from(&grid).unwrap(), template);
+        assert!(matches!(ArrayGrid::<char, 2, 3>::try_from(&grid), Err(Error::WrongDimensions)));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let mut kernel = ArrayGrid::<u8, 3, 3>::filled(0);
+        let center = GridVec::from([1, 1]);
+        for adj_loc in kernel.neighbors(center, &Neighborhood::VonNeumann).collect::<Vec<_>>() {
+            kernel[adj_loc] = 1;
+        }
+
+        assert_eq!(kernel.cells, [[0, 1, 0], [1, 0, 1], [0, 1, 0]]);
+        assert_eq!(kernel.neighbors(GridVec::default(), &Neighborhood::Moore).count(), 3);
+    }
+}
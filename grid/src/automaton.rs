@@ -0,0 +1,260 @@
+use crate::{Grid, GridVec, Neighborhood, Rect};
+
+/// How neighbors are found for cells on the edge of the grid
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Edges {
+    /// Off-grid neighbors are left out of the neighbor list
+    Bounded,
+    /// Off-grid neighbors wrap around to the opposite edge
+    Wrapping,
+    /// The grid grows as needed. Off-grid cells are `T::default()`
+    Infinite
+}
+
+/// A grid which is stepped forward in time by applying the same rule to every cell at once
+pub struct Automaton<T> {
+    pub grid: Grid<T>,
+    pub neighborhood: Neighborhood,
+    pub edges: Edges,
+    /// Number of threads each step is split across. `1` steps on the calling thread
+    pub threads: usize,
+    /// Number of steps taken so far
+    pub generation: u64,
+    /// World location of `grid`'s top-left cell. Only moves when `edges` is `Infinite`
+    pub origin: GridVec
+}
+
+impl<T> Automaton<T>
+where
+    T: Clone + Default + PartialEq + Send + Sync
+{
+    pub fn new(grid: Grid<T>, neighborhood: Neighborhood) -> Self {
+        Automaton {
+            grid,
+            neighborhood,
+            edges: Edges::Bounded,
+            threads: 1,
+            generation: 0,
+            origin: GridVec::default()
+        }
+    }
+
+    /// Returns the cell at world location `loc`. With `Infinite` edges, cells outside the grid are
+    /// reported as `None` even though they are logically `T::default()`
+    pub fn get(&self, loc: GridVec) -> Option<&T> {
+        self.grid.get(loc - self.origin)
+    }
+
+    /// Replaces every cell with `rule(cell, neighbors)` and advances `generation` by one
+    pub fn step<F>(&mut self, rule: F)
+    where
+        F: Fn(&T, &[&T]) -> T + Sync
+    {
+        let offsets = self.neighborhood.offsets();
+        match self.edges {
+            Edges::Bounded | Edges::Wrapping => {
+                self.grid = next_grid(&self.grid, offsets, self.edges, None, self.threads, &rule);
+            },
+            Edges::Infinite => {
+                let reach = offsets.iter()
+                    .map(|v| v.i.abs().max(v.j.abs()))
+                    .max()
+                    .unwrap_or(0);
+                let padded = self.grid.pad(reach as usize, T::default());
+                let background = T::default();
+                let next = next_grid(&padded, offsets, Edges::Bounded, Some(&background), self.threads, &rule);
+                let (trimmed, shift) = trim_default(&next);
+                self.grid = trimmed;
+                self.origin += shift - GridVec::from([reach, reach]);
+            }
+        }
+        self.generation += 1;
+    }
+
+    /// Takes `n` steps with the same rule
+    pub fn run<F>(&mut self, n: u64, rule: F)
+    where
+        F: Fn(&T, &[&T]) -> T + Sync
+    {
+        for _ in 0..n {
+            self.step(&rule);
+        }
+    }
+}
+
+/// Returns the next generation of `grid`, where each cell becomes `rule(cell, neighbors)`.
+/// Neighbors which fall off the grid are left out.
+pub fn step<T, F>(grid: &Grid<T>, neighborhood: &Neighborhood, rule: F) -> Grid<T>
+where
+    T: Clone + Send + Sync,
+    F: Fn(&T, &[&T]) -> T + Sync
+{
+    next_grid(grid, neighborhood.offsets(), Edges::Bounded, None, 1, &rule)
+}
+
+/// Builds the rule for a Life-like automaton over `Grid<bool>`. A dead cell comes alive when its
+/// live neighbor count is in `born`, and a live cell stays alive when it is in `survive`. For
+/// example, Conway's Game of Life is `life_like(&[3], &[2, 3])`.
+pub fn life_like(born: &[usize], survive: &[usize]) -> impl Fn(&bool, &[&bool]) -> bool + Sync + use<> {
+    let born = born.to_vec();
+    let survive = survive.to_vec();
+    move |&alive, neighbors| {
+        let live_count = neighbors.iter().filter(|&&&n| n).count();
+        if alive {
+            survive.contains(&live_count)
+        } else {
+            born.contains(&live_count)
+        }
+    }
+}
+
+/// Computes the next generation of `grid`. `background` (if any) stands in for off-grid neighbors
+/// when `edges` is `Bounded`.
+fn next_grid<T, F>(
+    grid: &Grid<T>,
+    offsets: &[GridVec],
+    edges: Edges,
+    background: Option<&T>,
+    threads: usize,
+    rule: &F
+) -> Grid<T>
+where
+    T: Clone + Send + Sync,
+    F: Fn(&T, &[&T]) -> T + Sync
+{
+    let threads = threads.clamp(1, grid.row_count.max(1));
+    let rows = if threads == 1 {
+        next_rows(grid, 0..grid.row_count, offsets, edges, background, rule)
+    } else {
+        let chunk_len = grid.row_count.div_ceil(threads);
+        std::thread::scope(|scope| {
+            let handles = (0..grid.row_count)
+                .step_by(chunk_len)
+                .map(|start| {
+                    let end = (start + chunk_len).min(grid.row_count);
+                    scope.spawn(move || next_rows(grid, start..end, offsets, edges, background, rule))
+                })
+                .collect::<Vec<_>>();
+
+            handles.into_iter()
+                .flat_map(|h| h.join().unwrap())
+                .collect()
+        })
+    };
+
+    Grid {
+        rows,
+        row_count: grid.row_count,
+        col_count: grid.col_count
+    }
+}
+
+fn next_rows<T, F>(
+    grid: &Grid<T>,
+    row_range: std::ops::Range<usize>,
+    offsets: &[GridVec],
+    edges: Edges,
+    background: Option<&T>,
+    rule: &F
+) -> Vec<Vec<T>>
+where
+    F: Fn(&T, &[&T]) -> T
+{
+    let dims = GridVec::from([grid.row_count, grid.col_count]);
+    let mut neighbors = Vec::with_capacity(offsets.len());
+    row_range
+        .map(|i| (0..grid.col_count)
+            .map(|j| {
+                let loc = GridVec::from([i, j]);
+                neighbors.clear();
+                for &dir in offsets {
+                    let adj_loc = loc + dir;
+                    let adj = match edges {
+                        Edges::Wrapping => grid.get(GridVec {
+                            i: adj_loc.i.rem_euclid(dims.i),
+                            j: adj_loc.j.rem_euclid(dims.j)
+                        }),
+                        _ => grid.get(adj_loc).or(background)
+                    };
+                    if let Some(adj) = adj {
+                        neighbors.push(adj);
+                    }
+                }
+                rule(&grid[loc], &neighbors)
+            })
+            .collect()
+        )
+        .collect()
+}
+
+/// Strips rows/cols consisting only of `T::default()` from the edges of `grid`. Returns the trimmed
+/// grid along with the original location of its new top-left cell.
+fn trim_default<T: Clone + Default + PartialEq>(grid: &Grid<T>) -> (Grid<T>, GridVec) {
+    let background = T::default();
+    let live_rows = (0..grid.row_count)
+        .filter(|&i| grid.rows[i].iter().any(|c| *c != background))
+        .collect::<Vec<_>>();
+    let live_cols = (0..grid.col_count)
+        .filter(|&j| grid.rows.iter().any(|row| row[j] != background))
+        .collect::<Vec<_>>();
+
+    let (Some(&i_min), Some(&i_max), Some(&j_min), Some(&j_max)) =
+        (live_rows.first(), live_rows.last(), live_cols.first(), live_cols.last())
+    else {
+        let empty = Grid { rows: vec![], row_count: 0, col_count: 0 };
+        return (empty, GridVec::default());
+    };
+
+    let trimmed = grid.crop(Rect::new(
+        GridVec::from([i_min, j_min]),
+        GridVec::from([i_max + 1, j_max + 1])
+    ));
+
+    (trimmed, GridVec::from([i_min, j_min]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_life(s: &str) -> Grid<bool> {
+        Grid::from_iter(s.lines().map(|l| l.trim().chars().map(|c| c == '#'))).unwrap()
+    }
+
+    #[test]
+    fn test_blinker_oscillates() {
+        let blinker = parse_life(".....\n..#..\n..#..\n..#..\n.....");
+        let conway = life_like(&[3], &[2, 3]);
+
+        let once = step(&blinker, &Neighborhood::Moore, &conway);
+        assert_eq!(once.rows, parse_life(".....\n.....\n.###.\n.....\n.....").rows);
+
+        let twice = step(&once, &Neighborhood::Moore, &conway);
+        assert_eq!(twice.rows, blinker.rows);
+    }
+
+    #[test]
+    fn test_wrapping_glider_returns_home() {
+        let glider = parse_life(".#...\n..#..\n###..\n.....\n.....");
+        let mut automaton = Automaton::new(parse_life(".#...\n..#..\n###..\n.....\n....."), Neighborhood::Moore);
+        automaton.edges = Edges::Wrapping;
+        automaton.threads = 3;
+
+        // A glider moves one cell diagonally every 4 generations
+        automaton.run(20, life_like(&[3], &[2, 3]));
+        assert_eq!(automaton.generation, 20);
+        assert_eq!(automaton.grid.rows, glider.rows);
+    }
+
+    #[test]
+    fn test_infinite_glider_moves_origin() {
+        let glider = parse_life(".#.\n..#\n###");
+        let mut automaton = Automaton::new(parse_life(".#.\n..#\n###"), Neighborhood::Moore);
+        automaton.edges = Edges::Infinite;
+
+        automaton.run(8, life_like(&[3], &[2, 3]));
+        assert_eq!(automaton.grid.rows, glider.rows);
+        assert_eq!(automaton.origin, GridVec::from([2, 2]));
+        assert_eq!(automaton.get(GridVec::from([4, 4])), Some(&true));
+    }
+}
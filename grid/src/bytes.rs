@@ -0,0 +1,68 @@
+use crate::{Error, Grid, Result};
+
+impl Grid<u8> {
+    /// Builds a grid with one byte per cell from ASCII text, such as a puzzle input read with
+    /// `std::fs::read` or a memory-mapped file. Rows are split on `\n`, a trailing `\r` is dropped
+    /// from each row, and a final empty line is ignored. Each row is copied in one go rather than
+    /// char by char, so this is much cheaper than parsing a `Grid<char>` for large maps.
+    pub fn from_ascii_bytes(bytes: &[u8]) -> Result<Self> {
+        let bytes = bytes.strip_suffix(b"\n").unwrap_or(bytes);
+        if bytes.is_empty() {
+            return Ok(Grid { rows: vec![], row_count: 0, col_count: 0 });
+        }
+
+        let rows = bytes.split(|&b| b == b'\n')
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line).to_vec())
+            .collect::<Vec<_>>();
+
+        let col_count = rows[0].len();
+        if rows.iter().any(|row| row.len() != col_count) {
+            return Err(Error::InconsitantRowLengths);
+        }
+
+        Ok(Grid {
+            row_count: rows.len(),
+            col_count,
+            rows
+        })
+    }
+
+    /// Converts to a `Grid<char>`, treating each byte as an ASCII (or Latin-1) character
+    pub fn to_char_grid(&self) -> Grid<char> {
+        Grid {
+            rows: self.rows.iter().map(|row| row.iter().map(|&b| b as char).collect()).collect(),
+            row_count: self.row_count,
+            col_count: self.col_count
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GridVec;
+    use crate::viewport::Viewport;
+
+    #[test]
+    fn test_from_ascii_bytes() {
+        let grid = Grid::from_ascii_bytes(b"#.O\r\n.@.\r\n").unwrap();
+        assert_eq!(grid.rows, vec![b"#.O".to_vec(), b".@.".to_vec()]);
+        assert_eq!(grid[GridVec::from([1, 1])], b'@');
+
+        let text = "#.O\n.@.";
+        let char_grid = Grid::from_iter(text.lines().map(|l| l.chars())).unwrap();
+        assert_eq!(Grid::from_ascii_bytes(text.as_bytes()).unwrap().to_char_grid(), char_grid);
+
+        assert!(matches!(Grid::from_ascii_bytes(b"##\n#\n"), Err(Error::InconsitantRowLengths)));
+        assert_eq!(Grid::from_ascii_bytes(b"").unwrap().row_count, 0);
+    }
+
+    #[test]
+    fn test_renders_like_chars() {
+        let text = "#..#\n.@..\n#..#";
+        let bytes = Grid::from_ascii_bytes(text.as_bytes()).unwrap();
+        let viewport = Viewport { focus: GridVec::from([5, 5]), width: 40, height: 10 };
+
+        assert_eq!(bytes.format_viewport(&viewport), bytes.to_char_grid().format_viewport(&viewport));
+    }
+}
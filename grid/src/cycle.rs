@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Describes where a sequence of states starts repeating itself. The state at step `start` is the
+/// first state which shows up again, and it shows up again every `length` steps from then on.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize
+}
+
+impl Cycle {
+    /// Maps step `n` onto the earliest step which has the same state as step `n`
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// Finds the cycle in the sequence `initial`, `step(initial)`, `step(step(initial))`, ... by
+/// remembering every state seen so far. Never returns if the sequence doesn't cycle.
+pub fn find_cycle<S, F>(initial: S, mut step: F) -> Cycle
+where
+    S: Clone + Hash + Eq,
+    F: FnMut(&S) -> S
+{
+    let mut first_seen = HashMap::new();
+    let mut state = initial;
+    let mut n = 0;
+    loop {
+        if let Some(&start) = first_seen.get(&state) {
+            return Cycle { start, length: n - start };
+        }
+        let next = step(&state);
+        first_seen.insert(state, n);
+        state = next;
+        n += 1;
+    }
+}
+
+/// Same as `find_cycle`, but uses Brent's algorithm, which only ever holds two states at a time.
+/// `step` gets called more often than with `find_cycle`, so it must be deterministic.
+pub fn find_cycle_brent<S, F>(initial: S, mut step: F) -> Cycle
+where
+    S: Clone + PartialEq,
+    F: FnMut(&S) -> S
+{
+    // Find the cycle length by racing the hare ahead of a tortoise which teleports to the hare
+    // every time the hare has taken a power of 2 steps
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // Find the start of the cycle by walking two states `length` apart until they meet
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Returns the state after `n` steps, skipping over whole cycles once one is found. Returns the
+/// state along with the cycle, if the sequence started repeating within `n` steps.
+pub fn state_at<S, F>(initial: S, mut step: F, n: usize) -> (S, Option<Cycle>)
+where
+    S: Clone + Hash + Eq,
+    F: FnMut(&S) -> S
+{
+    let mut first_seen = HashMap::new();
+    let mut history = vec![];
+    let mut state = initial;
+    for t in 0..n {
+        if let Some(&start) = first_seen.get(&state) {
+            let cycle = Cycle { start, length: t - start };
+            let state = history.swap_remove(cycle.reduce(n));
+            return (state, Some(cycle));
+        }
+        let next = step(&state);
+        first_seen.insert(state.clone(), t);
+        history.push(state);
+        state = next;
+    }
+
+    (state, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Grid, Neighborhood};
+    use crate::automaton::{step, life_like};
+
+    fn rho(x: &u64) -> u64 {
+        (x*x + 1) % 255
+    }
+
+    #[test]
+    fn test_brent_agrees_with_hashing() {
+        for initial in 0..255 {
+            assert_eq!(find_cycle(initial, rho), find_cycle_brent(initial, rho));
+        }
+    }
+
+    #[test]
+    fn test_state_at_skips_cycles() {
+        let initial = 3;
+        let mut expected = initial;
+        for _ in 0..1000 {
+            expected = rho(&expected);
+        }
+
+        let (state, cycle) = state_at(initial, rho, 1000);
+        assert_eq!(state, expected);
+        assert_eq!(cycle, Some(find_cycle(initial, rho)));
+        assert_eq!(state_at(initial, rho, 0), (initial, None));
+    }
+
+    #[test]
+    fn test_grid_states() {
+        let blinker = Grid::from_iter(
+            [".....", ".....", ".###.", ".....", "....."].into_iter()
+                .map(|l| l.chars().map(|c| c == '#'))
+        ).unwrap();
+        let conway = life_like(&[3], &[2, 3]);
+        let tick = |g: &Grid<bool>| step(g, &Neighborhood::Moore, &conway);
+
+        assert_eq!(find_cycle(blinker.clone(), tick), Cycle { start: 0, length: 2 });
+        assert_eq!(find_cycle_brent(blinker, tick), Cycle { start: 0, length: 2 });
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::{BTreeSet, VecDeque};
+
+use crate::{Error, Grid, GridVec, Neighborhood, Result};
+
+impl<T> Grid<T> {
+    /// Orders every cell so that each cell comes before all cells it can step to. Cell `a` can step
+    /// to cell `b` when `b` is in `a`'s neighborhood and `step(&grid[a], &grid[b])` is true. Returns
+    /// `Error::NotAcyclic` if some cell can step its way back to itself.
+    pub fn topological_order<F>(&self, neighborhood: &Neighborhood, step: F) -> Result<Vec<GridVec>>
+    where
+        F: Fn(&T, &T) -> bool
+    {
+        let offsets = neighborhood.offsets();
+        let successors = |loc: GridVec| offsets.iter()
+            .map(|&dir| loc + dir)
+            .filter(|&adj_loc| match self.get(adj_loc) {
+                Some(adj) => step(&self[loc], adj),
+                None => false
+            })
+            .collect::<Vec<_>>();
+
+        let mut in_degrees = Grid::new(self.row_count, self.col_count, 0usize);
+        for loc in self.locs() {
+            for next_loc in successors(loc) {
+                in_degrees[next_loc] += 1;
+            }
+        }
+
+        let mut queue = self.locs()
+            .filter(|&loc| in_degrees[loc] == 0)
+            .collect::<VecDeque<_>>();
+        let mut order = Vec::with_capacity(self.row_count * self.col_count);
+        while let Some(loc) = queue.pop_front() {
+            order.push(loc);
+            for next_loc in successors(loc) {
+                in_degrees[next_loc] -= 1;
+                if in_degrees[next_loc] == 0 {
+                    queue.push_back(next_loc);
+                }
+            }
+        }
+
+        if order.len() < self.row_count * self.col_count {
+            return Err(Error::NotAcyclic);
+        }
+
+        Ok(order)
+    }
+
+    /// Counts, for every cell, the distinct step paths which lead from that cell to a target cell.
+    /// A target cell counts as a path to itself. See `topological_order` for how steps work.
+    pub fn count_paths<F, G>(&self, neighborhood: &Neighborhood, step: F, is_target: G) -> Result<Grid<u64>>
+    where
+        F: Fn(&T, &T) -> bool,
+        G: Fn(GridVec, &T) -> bool
+    {
+        let order = self.topological_order(neighborhood, &step)?;
+        let offsets = neighborhood.offsets();
+
+        let mut counts = Grid::new(self.row_count, self.col_count, 0);
+        for &loc in order.iter().rev() {
+            let mut count = is_target(loc, &self[loc]) as u64;
+            for &dir in offsets.iter() {
+                if let Some(adj) = self.get(loc + dir) && step(&self[loc], adj) {
+                    count += counts[loc + dir];
+                }
+            }
+            counts[loc] = count;
+        }
+
+        Ok(counts)
+    }
+
+    /// Finds, for every cell, the set of target cells which can be reached from it by stepping.
+    /// See `topological_order` for how steps work.
+    pub fn reachable_targets<F, G>(&self, neighborhood: &Neighborhood, step: F, is_target: G) -> Result<Grid<BTreeSet<GridVec>>>
+    where
+        F: Fn(&T, &T) -> bool,
+        G: Fn(GridVec, &T) -> bool
+    {
+        let order = self.topological_order(neighborhood, &step)?;
+        let offsets = neighborhood.offsets();
+
+        let mut reachable = Grid::new(self.row_count, self.col_count, BTreeSet::new());
+        for &loc in order.iter().rev() {
+            let mut targets = BTreeSet::new();
+            if is_target(loc, &self[loc]) {
+                targets.insert(loc);
+            }
+            for &dir in offsets.iter() {
+                if let Some(adj) = self.get(loc + dir) && step(&self[loc], adj) {
+                    targets.extend(reachable[loc + dir].iter().copied());
+                }
+            }
+            reachable[loc] = targets;
+        }
+
+        Ok(reachable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_heights(s: &str) -> Grid<u8> {
+        Grid::from_iter(s.lines().map(|l| l.trim().chars().map(|c| c.to_digit(10).unwrap() as u8))).unwrap()
+    }
+
+    #[test]
+    fn test_trail_counts() {
+        let grid = parse_heights("\
+            0123
+            1234
+            8765
+            9876");
+        let climb = |&from: &u8, &to: &u8| to == from + 1;
+        let is_peak = |_, &h: &u8| h == 9;
+        let trailhead = GridVec::from([0, 0]);
+
+        let counts = grid.count_paths(&Neighborhood::VonNeumann, climb, is_peak).unwrap();
+        assert_eq!(counts[trailhead], 16);
+
+        let reachable = grid.reachable_targets(&Neighborhood::VonNeumann, climb, is_peak).unwrap();
+        assert_eq!(reachable[trailhead], BTreeSet::from([GridVec::from([3, 0])]));
+    }
+
+    #[test]
+    fn test_cycles_are_rejected() {
+        let grid = parse_heights("01\n10");
+        let result = grid.count_paths(&Neighborhood::VonNeumann, |a, b| a != b, |_, _| true);
+        assert!(matches!(result, Err(Error::NotAcyclic)));
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::BTreeSet;
+use std::fmt::{Display, Write};
+
+use crate::{AsChar, Grid, GridVec, ANSI_BG_GREEN, ANSI_BG_RED, ANSI_RESET};
+
+impl<T: PartialEq> Grid<T> {
+    /// Returns every location where `self` and `other` differ, in row-major order. If the grids have
+    /// different dimensions, locations which are only on one of them count as differing.
+    pub fn diff(&self, other: &Grid<T>) -> Vec<GridVec> {
+        let row_count = self.row_count.max(other.row_count);
+        let col_count = self.col_count.max(other.col_count);
+
+        let mut diff_locs = vec![];
+        for i in 0..row_count {
+            for j in 0..col_count {
+                let loc = GridVec::from([i, j]);
+                if self.get(loc) != other.get(loc) {
+                    diff_locs.push(loc);
+                }
+            }
+        }
+
+        diff_locs
+    }
+}
+
+impl<T: PartialEq + Display> Grid<T> {
+    /// Lays `self` and `other` out side by side, with cells that differ highlighted red on the left
+    /// and green on the right
+    pub fn format_diff(&self, other: &Grid<T>) -> String {
+        self.format_diff_with(other, |out, c| write!(out, "{}", c).unwrap())
+    }
+
+    pub fn render_diff(&self, other: &Grid<T>) {
+        print!("{}", self.format_diff(other));
+    }
+}
+
+impl<T: PartialEq + AsChar> Grid<T> {
+    /// Like `format_diff`, but draws cells the way `Grid::render` does, which suits byte grids
+    pub fn format_char_diff(&self, other: &Grid<T>) -> String {
+        self.format_diff_with(other, |out, c| out.push(c.as_char()))
+    }
+
+    pub fn render_char_diff(&self, other: &Grid<T>) {
+        print!("{}", self.format_char_diff(other));
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    fn format_diff_with(&self, other: &Grid<T>, write_cell: fn(&mut String, &T)) -> String {
+        let diff_locs = BTreeSet::from_iter(self.diff(other));
+        let row_count = self.row_count.max(other.row_count);
+
+        let mut out = String::new();
+        for i in 0..row_count {
+            format_diff_row(&mut out, self, i, &diff_locs, ANSI_BG_RED, write_cell);
+            out.push_str(" | ");
+            format_diff_row(&mut out, other, i, &diff_locs, ANSI_BG_GREEN, write_cell);
+            out.push('\n');
+        }
+
+        out
+    }
+}
+
+fn format_diff_row<T>(
+    out: &mut String,
+    grid: &Grid<T>,
+    i: usize,
+    diff_locs: &BTreeSet<GridVec>,
+    hl: &str,
+    write_cell: fn(&mut String, &T)
+) {
+    for j in 0..grid.col_count {
+        let loc = GridVec::from([i, j]);
+        match grid.get(loc) {
+            Some(c) if diff_locs.contains(&loc) => {
+                out.push_str(hl);
+                write_cell(out, c);
+                out.push_str(ANSI_RESET);
+            },
+            Some(c) => write_cell(out, c),
+            None => out.push(' ')
+        }
+    }
+}
+
+/// Asserts that two grids are equal. On failure, the panic message includes a side-by-side diff of
+/// the two grids (see `Grid::format_diff`).
+#[macro_export]
+macro_rules! assert_grid_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if left != right {
+                    panic!(
+                        "assertion `left == right` failed ({} cells differ)\n{}",
+                        left.diff(right).len(),
+                        left.format_diff(right)
+                    );
+                }
+            }
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if left != right {
+                    panic!(
+                        "assertion `left == right` failed: {} ({} cells differ)\n{}",
+                        format_args!($($arg)+),
+                        left.diff(right).len(),
+                        left.format_diff(right)
+                    );
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::parse;
+
+    #[test]
+    fn test_diff() {
+        let a = parse("#.O\n.@.");
+        let b = parse("#O.\n.@.\n...");
+        assert_eq!(
+            a.diff(&b),
+            vec![
+                GridVec::from([0, 1]),
+                GridVec::from([0, 2]),
+                GridVec::from([2, 0]),
+                GridVec::from([2, 1]),
+                GridVec::from([2, 2]),
+            ]
+        );
+        assert!(a.diff(&a.clone()).is_empty());
+        assert_grid_eq!(a, a.clone());
+        assert_grid_eq!(Grid::new(2, 2, 0u32), Grid::new(2, 2, 0u32));
+    }
+
+    #[test]
+    fn test_format_byte_diff() {
+        let a = Grid::from_ascii_bytes(b"#.O\n.@.").unwrap();
+        let b = Grid::from_ascii_bytes(b"#O.\n.@.").unwrap();
+        let expected = format!(
+            "#{red}.{reset}{red}O{reset} | #{green}O{reset}{green}.{reset}\n.@. | .@.\n",
+            red = ANSI_BG_RED,
+            green = ANSI_BG_GREEN,
+            reset = ANSI_RESET
+        );
+        assert_eq!(a.format_char_diff(&b), expected);
+        assert!(a.format_diff(&b).starts_with(&format!("35{}46", ANSI_BG_RED)));
+    }
+
+    #[test]
+    #[should_panic(expected = "2 cells differ")]
+    fn test_assert_grid_eq_fails() {
+        assert_grid_eq!(parse("#.O"), parse("#O."));
+    }
+}
@@ -0,0 +1,142 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+use crate::{Grid, GridVec, Neighborhood};
+
+/// How far apart two cells are
+#[derive(Clone, Debug)]
+pub enum Metric {
+    /// Orthogonal steps of cost 1
+    Manhattan,
+    /// Orthogonal or diagonal steps of cost 1
+    Chebyshev,
+    /// Arbitrary steps, each with its own cost
+    Custom(Vec<(GridVec, u32)>)
+}
+
+impl Metric {
+    fn steps(&self) -> Vec<(GridVec, u32)> {
+        match self {
+            Metric::Manhattan => Neighborhood::VonNeumann.offsets().iter().map(|&dir| (dir, 1)).collect(),
+            Metric::Chebyshev => Neighborhood::Moore.offsets().iter().map(|&dir| (dir, 1)).collect(),
+            Metric::Custom(steps) => steps.clone()
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Returns the Manhattan distance from every cell to the nearest of `sources`, moving only
+    /// through cells for which `passable` is true. See `distance_field_with`.
+    pub fn distance_field<F>(&self, sources: impl IntoIterator<Item = GridVec>, passable: F) -> Grid<Option<u32>>
+    where
+        F: Fn(GridVec, &T) -> bool
+    {
+        self.distance_field_with(sources, passable, &Metric::Manhattan)
+    }
+
+    /// Returns the distance under `metric` from every cell to the nearest of `sources`, moving only
+    /// through cells for which `passable` is true. Sources are always at distance 0, even if they
+    /// aren't passable themselves. Cells which can't be reached are `None`.
+    pub fn distance_field_with<F>(
+        &self,
+        sources: impl IntoIterator<Item = GridVec>,
+        passable: F,
+        metric: &Metric
+    ) -> Grid<Option<u32>>
+    where
+        F: Fn(GridVec, &T) -> bool
+    {
+        let steps = metric.steps();
+        let mut dists = Grid::new(self.row_count, self.col_count, None);
+
+        let can_enter = |loc: GridVec| match self.get(loc) {
+            Some(c) => passable(loc, c),
+            None => false
+        };
+
+        if steps.iter().all(|&(_, cost)| cost == 1) {
+            // Unit costs, so a plain BFS visits cells in order of distance
+            let mut queue = VecDeque::new();
+            for loc in sources {
+                if self.get(loc).is_some() && dists[loc].is_none() {
+                    dists[loc] = Some(0);
+                    queue.push_back(loc);
+                }
+            }
+
+            while let Some(loc) = queue.pop_front() {
+                let dist = dists[loc].unwrap();
+                for &(dir, _) in steps.iter() {
+                    let next_loc = loc + dir;
+                    if can_enter(next_loc) && dists[next_loc].is_none() {
+                        dists[next_loc] = Some(dist + 1);
+                        queue.push_back(next_loc);
+                    }
+                }
+            }
+        } else {
+            let mut heap = BinaryHeap::new();
+            for loc in sources {
+                if self.get(loc).is_some() {
+                    dists[loc] = Some(0);
+                    heap.push(Reverse((0, loc)));
+                }
+            }
+
+            while let Some(Reverse((dist, loc))) = heap.pop() {
+                if dists[loc].is_some_and(|best| best < dist) {
+                    continue;
+                }
+                for &(dir, cost) in steps.iter() {
+                    let next_loc = loc + dir;
+                    let next_dist = dist + cost;
+                    if can_enter(next_loc) && dists[next_loc].is_none_or(|best| next_dist < best) {
+                        dists[next_loc] = Some(next_dist);
+                        heap.push(Reverse((next_dist, next_loc)));
+                    }
+                }
+            }
+        }
+
+        dists
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::parse;
+
+    #[test]
+    fn test_manhattan_around_walls() {
+        let grid = parse("S.#.\n..#.\n....");
+        let dists = grid.distance_field([GridVec::from([0, 0])], |_, &c| c != '#');
+
+        assert_eq!(dists.rows, vec![
+            vec![Some(0), Some(1), None,    Some(7)],
+            vec![Some(1), Some(2), None,    Some(6)],
+            vec![Some(2), Some(3), Some(4), Some(5)],
+        ]);
+    }
+
+    #[test]
+    fn test_multi_source_metrics() {
+        let grid = Grid::new(3, 5, ());
+        let sources = [GridVec::from([0, 0]), GridVec::from([2, 4])];
+
+        let chebyshev = grid.distance_field_with(sources, |_, _| true, &Metric::Chebyshev);
+        assert_eq!(chebyshev[GridVec::from([1, 2])], Some(2));
+        assert_eq!(chebyshev[GridVec::from([0, 4])], Some(2));
+
+        // Horizontal steps are cheap, vertical steps are expensive
+        let weighted = Metric::Custom(vec![
+            (GridVec::from([0, 1]), 1),
+            (GridVec::from([0,-1]), 1),
+            (GridVec::from([1, 0]), 10),
+            (GridVec::from([-1, 0]), 10),
+        ]);
+        let custom = grid.distance_field_with(sources, |_, _| true, &weighted);
+        assert_eq!(custom[GridVec::from([1, 2])], Some(12));
+        assert_eq!(custom[GridVec::from([2, 0])], Some(4));
+    }
+}
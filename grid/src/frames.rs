@@ -0,0 +1,290 @@
+//! Compact binary recordings of a grid changing over time.
+//!
+//! Layout (all integers are LEB128 varints unless noted):
+//!
+//! - header: the bytes `GRDF`, a format version byte, `row_count`, `col_count`
+//! - per frame: a kind byte (`0` keyframe, `1` delta), the number of new palette entries followed
+//!   by their values, then runs of `(length, code)` covering every cell in row-major order
+//!
+//! A run's code is a palette index plus one. In delta frames, code `0` means "unchanged since the
+//! previous frame". The palette only grows, so indices stay valid for the rest of the recording.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::{Error, Grid, Result};
+
+const MAGIC: &[u8; 4] = b"GRDF";
+const VERSION: u8 = 1;
+const KEYFRAME: u8 = 0;
+const DELTA: u8 = 1;
+
+/// Encodes a sequence of same-sized grids
+pub struct FrameWriter<T> {
+    pub row_count: usize,
+    pub col_count: usize,
+    /// Every `keyframe_interval`th frame is stored whole, which bounds how far back the reader has
+    /// to start decoding from
+    pub keyframe_interval: usize,
+    out: Vec<u8>,
+    palette: HashMap<T, u32>,
+    prev: Option<Vec<T>>,
+    frame_count: usize
+}
+
+impl<T> FrameWriter<T>
+where
+    T: Copy + Eq + Hash + Into<u32>
+{
+    pub fn new(row_count: usize, col_count: usize) -> Self {
+        let mut out = MAGIC.to_vec();
+        out.push(VERSION);
+        write_varint(&mut out, row_count as u64);
+        write_varint(&mut out, col_count as u64);
+
+        FrameWriter {
+            row_count,
+            col_count,
+            keyframe_interval: 64,
+            out,
+            palette: HashMap::new(),
+            prev: None,
+            frame_count: 0
+        }
+    }
+
+    /// Appends `grid` as the next frame
+    pub fn push(&mut self, grid: &Grid<T>) -> Result<()> {
+        if grid.row_count != self.row_count || grid.col_count != self.col_count {
+            return Err(Error::WrongDimensions);
+        }
+
+        let cells = grid.rows.iter().flatten().copied().collect::<Vec<_>>();
+        let is_keyframe = self.frame_count.is_multiple_of(self.keyframe_interval.max(1));
+        self.out.push(if is_keyframe { KEYFRAME } else { DELTA });
+
+        let mut new_values = vec![];
+        for &cell in &cells {
+            if !self.palette.contains_key(&cell) {
+                self.palette.insert(cell, self.palette.len() as u32);
+                new_values.push(cell);
+            }
+        }
+        write_varint(&mut self.out, new_values.len() as u64);
+        for value in new_values {
+            write_varint(&mut self.out, value.into() as u64);
+        }
+
+        let codes = cells.iter()
+            .enumerate()
+            .map(|(k, c)| match &self.prev {
+                Some(prev) if !is_keyframe && prev[k] == *c => 0,
+                _ => self.palette[c] + 1
+            });
+        write_runs(&mut self.out, codes);
+
+        self.prev = Some(cells);
+        self.frame_count += 1;
+        Ok(())
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    /// Returns the encoded recording
+    pub fn finish(self) -> Vec<u8> {
+        self.out
+    }
+}
+
+/// Decodes a recording made by `FrameWriter`, giving random access to its frames
+pub struct FrameReader<'a, T> {
+    pub row_count: usize,
+    pub col_count: usize,
+    data: &'a [u8],
+    palette: Vec<T>,
+    /// Start of each frame's runs, and whether the frame is a keyframe
+    frames: Vec<(usize, bool)>
+}
+
+impl<'a, T> FrameReader<'a, T>
+where
+    T: Copy + TryFrom<u32>
+{
+    /// Checks the header and indexes every frame
+    pub fn new(data: &'a [u8]) -> Result<Self> {
+        if data.len() < 5 || &data[..4] != MAGIC || data[4] != VERSION {
+            return Err(Error::InvalidFrameData);
+        }
+
+        let mut pos = 5;
+        let row_count = read_varint(data, &mut pos)? as usize;
+        let col_count = read_varint(data, &mut pos)? as usize;
+        let cell_count = row_count.checked_mul(col_count).ok_or(Error::InvalidFrameData)?;
+
+        let mut reader = FrameReader { row_count, col_count, data, palette: vec![], frames: vec![] };
+        while pos < data.len() {
+            let is_keyframe = match data[pos] {
+                KEYFRAME => true,
+                DELTA if !reader.frames.is_empty() => false,
+                _ => return Err(Error::InvalidFrameData)
+            };
+            pos += 1;
+
+            let new_count = read_varint(data, &mut pos)?;
+            for _ in 0..new_count {
+                let value = u32::try_from(read_varint(data, &mut pos)?).map_err(|_| Error::InvalidFrameData)?;
+                reader.palette.push(T::try_from(value).map_err(|_| Error::InvalidFrameData)?);
+            }
+
+            reader.frames.push((pos, is_keyframe));
+            reader.for_each_run(&mut pos, cell_count, |_, _, _| Ok(()))?;
+        }
+
+        Ok(reader)
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Reconstructs frame `n`, decoding forward from the nearest keyframe at or before it
+    pub fn frame(&self, n: usize) -> Result<Grid<T>> {
+        if n >= self.frames.len() {
+            return Err(Error::InvalidFrameData);
+        }
+        let start = (0..=n).rev().find(|&k| self.frames[k].1).unwrap();
+
+        let cell_count = self.row_count * self.col_count;
+        let mut cells: Vec<Option<T>> = vec![None; cell_count];
+        for &(mut pos, _) in &self.frames[start..=n] {
+            self.for_each_run(&mut pos, cell_count, |offset, length, code| {
+                if code > 0 {
+                    let value = self.palette[code as usize - 1];
+                    cells[offset..offset + length].fill(Some(value));
+                }
+                Ok(())
+            })?;
+        }
+
+        let cells = cells.into_iter().collect::<Option<Vec<_>>>().ok_or(Error::InvalidFrameData)?;
+        Grid::from_iter(cells.chunks(self.col_count.max(1)).map(|row| row.iter().copied()))
+    }
+
+    /// Reconstructs every frame in order
+    pub fn frames(&self) -> impl Iterator<Item = Result<Grid<T>>> + '_ {
+        (0..self.len()).map(|n| self.frame(n))
+    }
+
+    /// Calls `f(offset, length, code)` for each run of the frame starting at `pos`, checking that
+    /// the runs exactly cover the grid and only refer to known palette entries
+    fn for_each_run<F>(&self, pos: &mut usize, cell_count: usize, mut f: F) -> Result<()>
+    where
+        F: FnMut(usize, usize, u64) -> Result<()>
+    {
+        let mut offset = 0;
+        while offset < cell_count {
+            let length = read_varint(self.data, pos)? as usize;
+            let code = read_varint(self.data, pos)?;
+            if length == 0 || length > cell_count - offset || code > self.palette.len() as u64 {
+                return Err(Error::InvalidFrameData);
+            }
+            f(offset, length, code)?;
+            offset += length;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_runs(out: &mut Vec<u8>, codes: impl Iterator<Item = u32>) {
+    let mut run: Option<(u32, u64)> = None;
+    for code in codes {
+        match &mut run {
+            Some((run_code, length)) if *run_code == code => *length += 1,
+            _ => {
+                if let Some((run_code, length)) = run {
+                    write_varint(out, length);
+                    write_varint(out, run_code as u64);
+                }
+                run = Some((code, 1));
+            }
+        }
+    }
+    if let Some((run_code, length)) = run {
+        write_varint(out, length);
+        write_varint(out, run_code as u64);
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        out.push((n as u8 & 0x7f) | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn read_varint(data: &[u8], pos: &mut usize) -> Result<u64> {
+    let mut n = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *data.get(*pos).ok_or(Error::InvalidFrameData)?;
+        *pos += 1;
+        n |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(n);
+        }
+    }
+
+    Err(Error::InvalidFrameData)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GridVec;
+
+    #[test]
+    fn test_round_trip() {
+        let mut grid = Grid::<char>::parse("#####\n#@..#\n#...#\n#####").unwrap();
+        let mut frames = vec![grid.clone()];
+        for j in 1..3 {
+            grid[GridVec::from([1, j])] = '.';
+            grid[GridVec::from([1, j + 1])] = '@';
+            frames.push(grid.clone());
+        }
+        grid[GridVec::from([2, 2])] = 'O';
+        frames.push(grid.clone());
+
+        let mut writer = FrameWriter::new(4, 5);
+        writer.keyframe_interval = 3;
+        for frame in &frames {
+            writer.push(frame).unwrap();
+        }
+        let data = writer.finish();
+        assert!(data.len() < frames.len() * 4 * 6);
+
+        let reader = FrameReader::<char>::new(&data).unwrap();
+        assert_eq!(reader.len(), frames.len());
+        for (n, frame) in frames.iter().enumerate().rev() {
+            assert_eq!(&reader.frame(n).unwrap(), frame);
+        }
+    }
+
+    #[test]
+    fn test_rejects_bad_data() {
+        let mut writer = FrameWriter::<u8>::new(2, 2);
+        assert!(matches!(writer.push(&Grid::new(3, 2, 0)), Err(Error::WrongDimensions)));
+        writer.push(&Grid::new(2, 2, 7)).unwrap();
+        let data = writer.finish();
+
+        assert!(FrameReader::<u8>::new(&data).is_ok());
+        assert!(matches!(FrameReader::<u8>::new(&data[..data.len() - 1]), Err(Error::InvalidFrameData)));
+        assert!(matches!(FrameReader::<u8>::new(b"GRDX"), Err(Error::InvalidFrameData)));
+    }
+}
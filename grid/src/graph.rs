@@ -0,0 +1,117 @@
+//! Conversion of grids into `petgraph` graphs, enabled by the `petgraph` feature
+
+use std::collections::HashMap;
+
+use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::graphmap::DiGraphMap;
+
+use crate::{Grid, GridVec, Neighborhood};
+
+/// A graph built from a grid, with one node per passable cell. Each node's weight is the location
+/// of its cell.
+pub struct GridGraph<W> {
+    pub graph: DiGraph<GridVec, W>,
+    pub nodes: HashMap<GridVec, NodeIndex>
+}
+
+impl<W> GridGraph<W> {
+    /// Returns the node for the cell at `loc`, if that cell was passable
+    pub fn node(&self, loc: GridVec) -> Option<NodeIndex> {
+        self.nodes.get(&loc).copied()
+    }
+
+    /// Returns the location of the cell behind `node`
+    pub fn loc(&self, node: NodeIndex) -> GridVec {
+        self.graph[node]
+    }
+}
+
+impl<T> Grid<T> {
+    /// Builds a directed graph with a node for every cell where `passable` is true, and an edge from
+    /// each of those to each passable neighbor, weighted by `weight(from, to)`
+    pub fn to_graph<W, P, F>(&self, neighborhood: &Neighborhood, passable: P, weight: F) -> GridGraph<W>
+    where
+        P: Fn(GridVec, &T) -> bool,
+        F: Fn((GridVec, &T), (GridVec, &T)) -> W
+    {
+        let mut graph = DiGraph::new();
+        let mut nodes = HashMap::new();
+        for loc in self.locs().filter(|&loc| passable(loc, &self[loc])) {
+            nodes.insert(loc, graph.add_node(loc));
+        }
+
+        // Edges are added in row-major order so edge indices don't depend on the hash order
+        for (loc, &node) in self.locs().filter_map(|loc| nodes.get(&loc).map(|node| (loc, node))) {
+            for adj_loc in self.neighbors(loc, neighborhood) {
+                if let Some(&adj_node) = nodes.get(&adj_loc) {
+                    graph.add_edge(node, adj_node, weight((loc, &self[loc]), (adj_loc, &self[adj_loc])));
+                }
+            }
+        }
+
+        GridGraph { graph, nodes }
+    }
+
+    /// Same as `to_graph`, but builds a `GraphMap` which uses cell locations as node ids directly
+    pub fn to_graph_map<W, P, F>(&self, neighborhood: &Neighborhood, passable: P, weight: F) -> DiGraphMap<GridVec, W>
+    where
+        P: Fn(GridVec, &T) -> bool,
+        F: Fn((GridVec, &T), (GridVec, &T)) -> W
+    {
+        let mut graph = DiGraphMap::new();
+        for loc in self.locs().filter(|&loc| passable(loc, &self[loc])) {
+            graph.add_node(loc);
+        }
+
+        let locs = graph.nodes().collect::<Vec<_>>();
+        for loc in locs {
+            for adj_loc in self.neighbors(loc, neighborhood) {
+                if graph.contains_node(adj_loc) {
+                    graph.add_edge(loc, adj_loc, weight((loc, &self[loc]), (adj_loc, &self[adj_loc])));
+                }
+            }
+        }
+
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::parse;
+    use petgraph::algo::dijkstra;
+    use petgraph::visit::EdgeRef;
+
+    #[test]
+    fn test_shortest_paths_through_maze() {
+        let maze = parse("\
+            S.#
+            .##
+            ..E");
+        let start = GridVec::from([0, 0]);
+        let end = GridVec::from([2, 2]);
+        let open = |_, &c: &char| c != '#';
+        // Leaving a cell costs 1, except leaving the start which costs 10
+        let cost = |(_, &from): (GridVec, &char), _: (GridVec, &char)| if from == 'S' { 10 } else { 1 };
+
+        let gg = maze.to_graph(&Neighborhood::VonNeumann, open, cost);
+        assert_eq!(gg.graph.node_count(), 6);
+        assert_eq!(gg.graph.edge_count(), 10);
+        let first_edges = gg.graph.edge_references()
+            .take(2)
+            .map(|e| (gg.loc(e.source()), gg.loc(e.target())))
+            .collect::<Vec<_>>();
+        assert_eq!(first_edges, vec![(start, GridVec::from([0, 1])), (start, GridVec::from([1, 0]))]);
+        let start_node = gg.node(start).unwrap();
+        assert_eq!(gg.loc(start_node), start);
+        assert_eq!(gg.node(GridVec::from([0, 2])), None);
+
+        let dists = dijkstra(&gg.graph, start_node, gg.node(end), |e| *e.weight());
+        assert_eq!(dists[&gg.node(end).unwrap()], 13);
+
+        let gm = maze.to_graph_map(&Neighborhood::VonNeumann, open, cost);
+        let dists = dijkstra(&gm, start, Some(end), |e| *e.weight());
+        assert_eq!(dists[&end], 13);
+    }
+}
@@ -0,0 +1,291 @@
+use std::iter::Enumerate;
+use std::ops::Index;
+use std::{slice, vec};
+
+use crate::{GridVec, Rect};
+
+/// A map from locations to values, for per-cell data on a grid of known size. Backed by a dense
+/// `Vec`, so lookups are a bounds check and an index instead of a hash or a tree walk.
+///
+/// Iteration is in row-major order, like a `BTreeMap<GridVec, V>`.
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct GridMap<V> {
+    bounds: Rect,
+    cells: Vec<Option<V>>,
+    len: usize
+}
+
+impl<V> GridMap<V> {
+    /// Creates an empty map which can hold keys inside `bounds`
+    pub fn new(bounds: Rect) -> Self {
+        GridMap {
+            bounds,
+            cells: std::iter::repeat_with(|| None)
+                .take(bounds.row_count() * bounds.col_count())
+                .collect(),
+            len: 0
+        }
+    }
+
+    /// The rectangle of locations which can be used as keys
+    pub fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn contains_key(&self, loc: GridVec) -> bool {
+        self.get(loc).is_some()
+    }
+
+    /// Returns the value at `loc`. Locations outside the bounds are never present.
+    pub fn get(&self, loc: GridVec) -> Option<&V> {
+        self.slot(loc).and_then(|k| self.cells[k].as_ref())
+    }
+
+    pub fn get_mut(&mut self, loc: GridVec) -> Option<&mut V> {
+        self.slot(loc).and_then(|k| self.cells[k].as_mut())
+    }
+
+    /// Sets the value at `loc`, returning the old value if there was one. Panics if `loc` is
+    /// outside the bounds.
+    pub fn insert(&mut self, loc: GridVec, value: V) -> Option<V> {
+        let k = self.expect_slot(loc);
+        let old = self.cells[k].replace(value);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    pub fn remove(&mut self, loc: GridVec) -> Option<V> {
+        let old = self.slot(loc).and_then(|k| self.cells[k].take());
+        if old.is_some() {
+            self.len -= 1;
+        }
+        old
+    }
+
+    /// Gets the slot for `loc` for in-place updates. Panics if `loc` is outside the bounds.
+    pub fn entry(&mut self, loc: GridVec) -> Entry<'_, V> {
+        let k = self.expect_slot(loc);
+        Entry {
+            loc,
+            slot: &mut self.cells[k],
+            len: &mut self.len
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.iter_mut().for_each(|c| *c = None);
+        self.len = 0;
+    }
+
+    /// Iterates over `(location, value)` pairs in row-major order
+    pub fn iter(&self) -> Iter<'_, V> {
+        Iter {
+            bounds: self.bounds,
+            cells: self.cells.iter().enumerate()
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (GridVec, &mut V)> {
+        let bounds = self.bounds;
+        self.cells.iter_mut()
+            .enumerate()
+            .filter_map(move |(k, c)| c.as_mut().map(|v| (loc_of(bounds, k), v)))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = GridVec> + '_ {
+        self.iter().map(|(loc, _)| loc)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.cells.iter().flatten()
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        self.cells.iter_mut().flatten()
+    }
+
+    pub fn into_keys(self) -> impl Iterator<Item = GridVec> {
+        self.into_iter().map(|(loc, _)| loc)
+    }
+
+    pub fn into_values(self) -> impl Iterator<Item = V> {
+        self.cells.into_iter().flatten()
+    }
+
+    fn slot(&self, loc: GridVec) -> Option<usize> {
+        if !self.bounds.contains(loc) {
+            return None;
+        }
+
+        let offset = loc - self.bounds.min;
+        Some(offset.i as usize * self.bounds.col_count() + offset.j as usize)
+    }
+
+    fn expect_slot(&self, loc: GridVec) -> usize {
+        match self.slot(loc) {
+            Some(k) => k,
+            None => panic!("{:?} is outside the map bounds {:?}", loc, self.bounds)
+        }
+    }
+}
+
+fn loc_of(bounds: Rect, k: usize) -> GridVec {
+    let col_count = bounds.col_count();
+    bounds.min + GridVec::from([k / col_count, k % col_count])
+}
+
+impl<V> Index<GridVec> for GridMap<V> {
+    type Output = V;
+
+    fn index(&self, index: GridVec) -> &Self::Output {
+        self.get(index).unwrap()
+    }
+}
+
+impl<V> IntoIterator for GridMap<V> {
+    type Item = (GridVec, V);
+    type IntoIter = IntoIter<V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            bounds: self.bounds,
+            cells: self.cells.into_iter().enumerate()
+        }
+    }
+}
+
+impl<'a, V> IntoIterator for &'a GridMap<V> {
+    type Item = (GridVec, &'a V);
+    type IntoIter = Iter<'a, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Borrowing iterator over a `GridMap`, in row-major order
+pub struct Iter<'a, V> {
+    bounds: Rect,
+    cells: Enumerate<slice::Iter<'a, Option<V>>>
+}
+
+impl<'a, V> Iterator for Iter<'a, V> {
+    type Item = (GridVec, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bounds = self.bounds;
+        self.cells.find_map(|(k, c)| c.as_ref().map(|v| (loc_of(bounds, k), v)))
+    }
+}
+
+/// Owning iterator over a `GridMap`, in row-major order
+pub struct IntoIter<V> {
+    bounds: Rect,
+    cells: Enumerate<vec::IntoIter<Option<V>>>
+}
+
+impl<V> Iterator for IntoIter<V> {
+    type Item = (GridVec, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bounds = self.bounds;
+        self.cells.find_map(|(k, c)| c.map(|v| (loc_of(bounds, k), v)))
+    }
+}
+
+/// A single slot of a `GridMap`, which may or may not hold a value
+pub struct Entry<'a, V> {
+    loc: GridVec,
+    slot: &'a mut Option<V>,
+    len: &'a mut usize
+}
+
+impl<'a, V> Entry<'a, V> {
+    pub fn key(&self) -> GridVec {
+        self.loc
+    }
+
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        if self.slot.is_none() {
+            *self.len += 1;
+        }
+        self.slot.get_or_insert_with(default)
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Updates the value in place if there is one
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        if let Some(v) = self.slot.as_mut() {
+            f(v);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut map = GridMap::new(Rect::new(GridVec::from([-1, -1]), GridVec::from([2, 3])));
+        assert_eq!(map.insert(GridVec::from([1, 2]), 'a'), None);
+        assert_eq!(map.insert(GridVec::from([-1, 0]), 'b'), None);
+        assert_eq!(map.insert(GridVec::from([1, 2]), 'c'), Some('a'));
+        assert_eq!(map.len(), 2);
+
+        assert_eq!(map.get(GridVec::from([1, 2])), Some(&'c'));
+        assert_eq!(map.get(GridVec::from([5, 5])), None);
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            vec![(GridVec::from([-1, 0]), &'b'), (GridVec::from([1, 2]), &'c')]
+        );
+        let mut visited = vec![];
+        for (loc, &c) in &map {
+            visited.push((loc, c));
+        }
+        assert_eq!(visited, vec![(GridVec::from([-1, 0]), 'b'), (GridVec::from([1, 2]), 'c')]);
+
+        assert_eq!(map.remove(GridVec::from([-1, 0])), Some('b'));
+        assert_eq!(map.remove(GridVec::from([-1, 0])), None);
+        assert_eq!(map.into_keys().collect::<Vec<_>>(), vec![GridVec::from([1, 2])]);
+    }
+
+    #[test]
+    fn test_entry() {
+        let mut map: GridMap<Vec<u32>> = GridMap::new(Rect::new(GridVec::default(), GridVec::from([2, 2])));
+        let loc = GridVec::from([1, 0]);
+        map.entry(loc).or_default().push(1);
+        map.entry(loc).and_modify(|v| v.push(2)).or_insert_with(|| vec![3]);
+
+        assert_eq!(map[loc], vec![1, 2]);
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.values().count(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "outside the map bounds")]
+    fn test_insert_out_of_bounds() {
+        GridMap::new(Rect::new(GridVec::default(), GridVec::from([2, 2]))).insert(GridVec::from([2, 0]), 0);
+    }
+}
@@ -0,0 +1,314 @@
+//! Interactive terminal browser for grids, enabled by the `tui` feature.
+//!
+//! Keys: arrows/`hjkl` move the cursor, `1`-`9` toggle highlight layers, `/` searches cell values,
+//! `n`/`N` jump between search matches, and `q`/`Esc` quits.
+
+use std::collections::BTreeSet;
+use std::fmt::Debug;
+use std::io::{self, Write};
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::{cursor, execute, queue, style, terminal};
+
+use crate::{AsChar, Grid, GridVec, ANSI_BRIGHT_BG, ANSI_RESET};
+use crate::viewport::Viewport;
+
+const LAYER_COLORS: [&str; 6] = [
+    "\x1b[41m", // red
+    "\x1b[42m", // green
+    "\x1b[44m", // blue
+    "\x1b[43m", // yellow
+    "\x1b[45m", // magenta
+    "\x1b[46m", // cyan
+];
+
+/// A named set of cells which can be highlighted in the inspector
+pub struct Layer {
+    pub name: String,
+    pub cells: BTreeSet<GridVec>,
+    pub visible: bool
+}
+
+/// Whether keystrokes are moving the cursor or typing a search
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Mode {
+    Browse,
+    Search(String)
+}
+
+pub struct Inspector<'a, T> {
+    grid: &'a Grid<T>,
+    pub cursor: GridVec,
+    pub layers: Vec<Layer>,
+    /// Cells whose `Debug` output contains the last search, in row-major order
+    pub matches: Vec<GridVec>,
+    mode: Mode,
+    /// Picks the character each cell is drawn with
+    glyph: fn(&T) -> char
+}
+
+impl<'a, T: Debug> Inspector<'a, T> {
+    /// Draws each cell with the first character of its `Debug` output
+    pub fn new(grid: &'a Grid<T>) -> Self {
+        Inspector::with_glyph(grid, debug_glyph)
+    }
+
+    /// Draws each cell with `glyph`. The status line and search still use `Debug`.
+    pub fn with_glyph(grid: &'a Grid<T>, glyph: fn(&T) -> char) -> Self {
+        Inspector {
+            grid,
+            cursor: GridVec::default(),
+            layers: vec![],
+            matches: vec![],
+            mode: Mode::Browse,
+            glyph
+        }
+    }
+
+    /// Adds a highlight layer, which can be toggled with the number key matching its position
+    pub fn add_layer(&mut self, name: &str, cells: BTreeSet<GridVec>) {
+        self.layers.push(Layer { name: name.to_string(), cells, visible: true });
+    }
+
+    /// Updates the inspector in response to a key press. Returns `false` once the user has quit.
+    pub fn handle_key(&mut self, key: KeyCode) -> bool {
+        if let Mode::Search(query) = &mut self.mode {
+            match key {
+                KeyCode::Char(c) => query.push(c),
+                KeyCode::Backspace => { query.pop(); },
+                KeyCode::Enter => {
+                    let query = query.clone();
+                    self.mode = Mode::Browse;
+                    self.search(&query);
+                },
+                KeyCode::Esc => self.mode = Mode::Browse,
+                _ => {}
+            }
+            return true;
+        }
+
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(GridVec::from([-1, 0])),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(GridVec::from([ 1, 0])),
+            KeyCode::Left | KeyCode::Char('h') => self.move_cursor(GridVec::from([ 0,-1])),
+            KeyCode::Right | KeyCode::Char('l') => self.move_cursor(GridVec::from([ 0, 1])),
+            KeyCode::Char('/') => self.mode = Mode::Search(String::new()),
+            KeyCode::Char('n') => self.jump_to_match(true),
+            KeyCode::Char('N') => self.jump_to_match(false),
+            KeyCode::Char(c @ '1'..='9') => {
+                let k = c.to_digit(10).unwrap() as usize - 1;
+                if let Some(layer) = self.layers.get_mut(k) {
+                    layer.visible = !layer.visible;
+                }
+            },
+            _ => {}
+        }
+
+        true
+    }
+
+    /// Takes over the terminal until the user quits
+    pub fn run(&mut self) -> io::Result<()> {
+        let mut out = io::stdout();
+        terminal::enable_raw_mode()?;
+        execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+        let result = self.event_loop(&mut out);
+
+        execute!(out, cursor::Show, terminal::LeaveAlternateScreen)?;
+        terminal::disable_raw_mode()?;
+        result
+    }
+
+    fn event_loop(&mut self, out: &mut impl Write) -> io::Result<()> {
+        loop {
+            let (width, height) = terminal::size()?;
+            self.draw(out, width as usize, height as usize)?;
+
+            if let Event::Key(key) = event::read()? && key.kind == KeyEventKind::Press && !self.handle_key(key.code) {
+                return Ok(());
+            }
+        }
+    }
+
+    fn draw(&self, out: &mut impl Write, width: usize, height: usize) -> io::Result<()> {
+        queue!(out, terminal::Clear(terminal::ClearType::All), cursor::MoveTo(0, 0))?;
+
+        let viewport = Viewport { focus: self.cursor, width, height };
+        let window = viewport.window(self.grid.row_count, self.grid.col_count);
+        let match_set = self.matches.iter().copied().collect::<BTreeSet<_>>();
+        for (line, i) in (window.min.i..window.max.i).enumerate() {
+            queue!(out, cursor::MoveTo(0, line as u16))?;
+            for j in window.min.j..window.max.j {
+                let loc = GridVec { i, j };
+                let color = self.layers.iter()
+                    .enumerate()
+                    .rev()
+                    .find(|(_, layer)| layer.visible && layer.cells.contains(&loc))
+                    .map(|(k, _)| LAYER_COLORS[k % LAYER_COLORS.len()]);
+
+                if loc == self.cursor {
+                    queue!(out, style::SetAttribute(style::Attribute::Reverse))?;
+                } else if match_set.contains(&loc) {
+                    queue!(out, style::Print(ANSI_BRIGHT_BG))?;
+                } else if let Some(color) = color {
+                    queue!(out, style::Print(color))?;
+                }
+                queue!(out, style::Print((self.glyph)(&self.grid[loc])), style::Print(ANSI_RESET))?;
+            }
+        }
+
+        let status_line = height.saturating_sub(2) as u16;
+        queue!(out, cursor::MoveTo(0, status_line), style::Print(self.status()))?;
+        queue!(out, cursor::MoveTo(0, status_line + 1), style::Print(self.layer_legend()))?;
+
+        out.flush()
+    }
+
+    /// Describes the cell under the cursor, or the search being typed
+    pub fn status(&self) -> String {
+        match &self.mode {
+            Mode::Search(query) => format!("/{}", query),
+            Mode::Browse => {
+                let value = self.grid.get(self.cursor)
+                    .map(|c| format!("{:?}", c))
+                    .unwrap_or_default();
+                let match_info = match self.matches.iter().position(|&m| m == self.cursor) {
+                    Some(k) => format!("  [match {}/{}]", k + 1, self.matches.len()),
+                    None if !self.matches.is_empty() => format!("  [{} matches]", self.matches.len()),
+                    None => String::new()
+                };
+                format!("({}, {}) = {}{}", self.cursor.i, self.cursor.j, value, match_info)
+            }
+        }
+    }
+
+    fn layer_legend(&self) -> String {
+        self.layers.iter()
+            .enumerate()
+            .map(|(k, layer)| format!(
+                "{}{} {}{} [{}]",
+                LAYER_COLORS[k % LAYER_COLORS.len()],
+                k + 1,
+                layer.name,
+                ANSI_RESET,
+                if layer.visible { "on" } else { "off" }
+            ))
+            .collect::<Vec<_>>()
+            .join("  ")
+    }
+
+    fn move_cursor(&mut self, dir: GridVec) {
+        if self.grid.get(self.cursor + dir).is_some() {
+            self.cursor += dir;
+        }
+    }
+
+    fn search(&mut self, query: &str) {
+        self.matches = self.grid.locs()
+            .filter(|&loc| !query.is_empty() && format!("{:?}", self.grid[loc]).contains(query))
+            .collect();
+        self.jump_to_match(true);
+    }
+
+    /// Moves the cursor to the next (or previous) match, wrapping around at either end
+    fn jump_to_match(&mut self, forward: bool) {
+        let next = if forward {
+            self.matches.iter().find(|&&m| m > self.cursor).or(self.matches.first())
+        } else {
+            self.matches.iter().rev().find(|&&m| m < self.cursor).or(self.matches.last())
+        };
+        if let Some(&loc) = next {
+            self.cursor = loc;
+        }
+    }
+}
+
+/// Browses `grid` interactively until the user quits
+pub fn inspect<T: Debug>(grid: &Grid<T>) -> io::Result<()> {
+    Inspector::new(grid).run()
+}
+
+/// Like `inspect`, but draws cells the way `Grid::render` does, which suits byte grids and
+/// `GridCell` enums
+pub fn inspect_chars<T: Debug + AsChar>(grid: &Grid<T>) -> io::Result<()> {
+    Inspector::with_glyph(grid, T::as_char).run()
+}
+
+/// Squeezes a cell's `Debug` output down to one character, dropping the quotes around chars and
+/// strings
+fn debug_glyph<T: Debug>(cell: &T) -> char {
+    format!("{:?}", cell)
+        .trim_matches(|c| c == '\'' || c == '"')
+        .chars()
+        .next()
+        .unwrap_or(' ')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::parse;
+
+    fn press<T: Debug>(inspector: &mut Inspector<T>, keys: &str) {
+        for c in keys.chars() {
+            let key = if c == '\n' { KeyCode::Enter } else { KeyCode::Char(c) };
+            assert!(inspector.handle_key(key));
+        }
+    }
+
+    #[test]
+    fn test_cursor_stays_on_grid() {
+        let grid = parse("ab\ncd");
+        let mut inspector = Inspector::new(&grid);
+
+        press(&mut inspector, "kh");
+        assert_eq!(inspector.cursor, GridVec::from([0, 0]));
+        press(&mut inspector, "jlll");
+        assert_eq!(inspector.cursor, GridVec::from([1, 1]));
+        assert_eq!(inspector.status(), "(1, 1) = 'd'");
+        assert!(!inspector.handle_key(KeyCode::Char('q')));
+    }
+
+    #[test]
+    fn test_search_and_layers() {
+        let grid = parse("#.#\n.#.");
+        let mut inspector = Inspector::new(&grid);
+        inspector.add_layer("walls", BTreeSet::from([GridVec::from([0, 0])]));
+
+        press(&mut inspector, "/#\n");
+        assert_eq!(inspector.matches.len(), 3);
+        assert_eq!(inspector.cursor, GridVec::from([0, 2]));
+        assert_eq!(inspector.status(), "(0, 2) = '#'  [match 2/3]");
+
+        press(&mut inspector, "nn");
+        assert_eq!(inspector.cursor, GridVec::from([0, 0]));
+        press(&mut inspector, "N");
+        assert_eq!(inspector.cursor, GridVec::from([1, 1]));
+
+        press(&mut inspector, "1");
+        assert!(!inspector.layers[0].visible);
+    }
+
+    #[test]
+    fn test_debug_glyph() {
+        assert_eq!(debug_glyph(&'#'), '#');
+        assert_eq!(debug_glyph(&Some(3)), 'S');
+        assert_eq!(debug_glyph(&42u8), '4');
+    }
+
+    #[test]
+    fn test_byte_grid() {
+        let grid = Grid::from_ascii_bytes(b"#.\n.#").unwrap();
+        let mut inspector = Inspector::with_glyph(&grid, u8::as_char);
+        assert_eq!(inspector.status(), "(0, 0) = 35");
+
+        let mut out = vec![];
+        inspector.draw(&mut out, 10, 5).unwrap();
+        assert!(String::from_utf8(out).unwrap().contains(&format!(".{}#", ANSI_RESET)));
+
+        press(&mut inspector, "/35\n");
+        assert_eq!(inspector.matches, vec![GridVec::from([0, 0]), GridVec::from([1, 1])]);
+    }
+}
@@ -0,0 +1,234 @@
+use std::borrow::Borrow;
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::ops::{Deref, Index, IndexMut};
+
+use crate::{AsChar, Grid, GridVec};
+
+/// A grid which records every cell handed out through `get_mut` or `IndexMut`, so that only the
+/// cells which changed need redrawing. Reads go straight through to the wrapped grid.
+///
+/// Edits can also be undone, but old values are only kept from the first `checkpoint` until
+/// `clear_history`, so a long-running animation without checkpoints doesn't grow a history.
+#[derive(Clone, Debug)]
+pub struct TrackedGrid<T> {
+    grid: Grid<T>,
+    /// Cells touched since the last redraw
+    dirty: BTreeSet<GridVec>,
+    /// Previous value of every touched cell since the oldest live checkpoint, oldest first
+    history: Vec<(GridVec, T)>,
+    /// Checkpoints which can still be rolled back to, oldest first. Writes only need to go in
+    /// `history` while there are any.
+    checkpoints: Vec<Checkpoint>,
+    next_checkpoint_id: u64
+}
+
+/// A point in a `TrackedGrid`'s history which it can be rolled back to
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Checkpoint {
+    id: u64,
+    history_len: usize
+}
+
+impl<T: Clone> Grid<T> {
+    /// Starts recording changes to the grid
+    pub fn track_changes(self) -> TrackedGrid<T> {
+        TrackedGrid {
+            grid: self,
+            dirty: BTreeSet::new(),
+            history: vec![],
+            checkpoints: vec![],
+            next_checkpoint_id: 0
+        }
+    }
+}
+
+impl<T: Clone> TrackedGrid<T> {
+    /// Returns the cell at `loc` for writing, marking it dirty. While a checkpoint is live, its
+    /// current value is remembered so it can be rolled back.
+    pub fn get_mut(&mut self, loc: GridVec) -> Option<&mut T> {
+        let cell = self.grid.get_mut(loc)?;
+        self.dirty.insert(loc);
+        if !self.checkpoints.is_empty() {
+            self.history.push((loc, cell.clone()));
+        }
+        Some(cell)
+    }
+
+    /// Cells written since the last call to `take_dirty` (or `format_dirty`)
+    pub fn dirty(&self) -> &BTreeSet<GridVec> {
+        &self.dirty
+    }
+
+    pub fn take_dirty(&mut self) -> BTreeSet<GridVec> {
+        std::mem::take(&mut self.dirty)
+    }
+
+    /// Marks the current state so it can be rolled back to. Writes are remembered from here on,
+    /// until `clear_history` is called.
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        let checkpoint = Checkpoint { id: self.next_checkpoint_id, history_len: self.history.len() };
+        self.next_checkpoint_id += 1;
+        self.checkpoints.push(checkpoint);
+        checkpoint
+    }
+
+    /// Undoes every write made since `checkpoint`. The restored cells are marked dirty, and
+    /// checkpoints taken after this one can no longer be rolled back to. Panics if the checkpoint
+    /// is stale, because `clear_history` has been called or an earlier checkpoint has been rolled
+    /// back to since it was taken.
+    pub fn rollback(&mut self, checkpoint: Checkpoint) {
+        let Some(k) = self.checkpoints.iter().position(|&c| c == checkpoint) else {
+            panic!("Can't roll back to a stale checkpoint");
+        };
+        self.checkpoints.truncate(k + 1);
+
+        while self.history.len() > checkpoint.history_len {
+            let (loc, value) = self.history.pop().unwrap();
+            self.grid[loc] = value;
+            self.dirty.insert(loc);
+        }
+    }
+
+    /// Forgets the history and stops remembering writes until the next checkpoint. Earlier
+    /// checkpoints can no longer be rolled back to.
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+        self.checkpoints.clear();
+    }
+
+    /// Stops tracking and returns the grid
+    pub fn into_inner(self) -> Grid<T> {
+        self.grid
+    }
+}
+
+impl<T: Clone + AsChar> TrackedGrid<T> {
+    /// Builds the terminal output which redraws just the dirty cells, assuming the grid was drawn
+    /// with its top-left cell at the top-left of the terminal. Clears the dirty set.
+    pub fn format_dirty(&mut self) -> String {
+        let mut out = String::new();
+        for loc in self.take_dirty() {
+            // Cursor positions are 1-based
+            write!(out, "\x1b[{};{}H{}", loc.i + 1, loc.j + 1, self.grid[loc].as_char()).unwrap();
+        }
+        write!(out, "\x1b[{};1H", self.grid.row_count + 1).unwrap();
+
+        out
+    }
+
+    pub fn render_dirty(&mut self) -> io::Result<()> {
+        let mut out = io::stdout();
+        out.write_all(self.format_dirty().as_bytes())?;
+        out.flush()
+    }
+}
+
+impl<T> Deref for TrackedGrid<T> {
+    type Target = Grid<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.grid
+    }
+}
+
+impl<T> Borrow<Grid<T>> for TrackedGrid<T> {
+    fn borrow(&self) -> &Grid<T> {
+        &self.grid
+    }
+}
+
+impl<T> Index<GridVec> for TrackedGrid<T> {
+    type Output = T;
+
+    fn index(&self, index: GridVec) -> &Self::Output {
+        &self.grid[index]
+    }
+}
+
+impl<T: Clone> IndexMut<GridVec> for TrackedGrid<T> {
+    fn index_mut(&mut self, index: GridVec) -> &mut Self::Output {
+        self.get_mut(index).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::push::{PushEngine, Shape};
+
+    #[test]
+    fn test_push_marks_dirty_and_rolls_back() {
+        let start = Grid::<char>::parse("#@O.#").unwrap();
+        let mut grid = start.clone().track_changes();
+        let engine = PushEngine { empty: '.', shapes: vec![Shape::single('@'), Shape::single('O')] };
+
+        let checkpoint = grid.checkpoint();
+        engine.push(&mut grid, GridVec::from([0, 1]), GridVec::from([0, 1]));
+        assert_eq!(grid.rows, Grid::<char>::parse("#.@O#").unwrap().rows);
+        assert_eq!(grid.take_dirty(), BTreeSet::from_iter([1, 2, 3].map(|j| GridVec::from([0, j]))));
+
+        grid.rollback(checkpoint);
+        assert_eq!(grid.dirty().len(), 3);
+        assert_eq!(grid.into_inner(), start);
+    }
+
+    #[test]
+    fn test_history_only_kept_after_checkpoint() {
+        let mut grid = Grid::<char>::parse("...").unwrap().track_changes();
+        grid[GridVec::from([0, 0])] = '#';
+        assert!(grid.history.is_empty());
+
+        let checkpoint = grid.checkpoint();
+        grid[GridVec::from([0, 1])] = '#';
+        assert_eq!(grid.history.len(), 1);
+
+        grid.clear_history();
+        grid[GridVec::from([0, 2])] = '#';
+        assert!(grid.history.is_empty());
+        assert_eq!(grid.dirty().len(), 3);
+        assert_ne!(grid.checkpoint(), checkpoint);
+    }
+
+    #[test]
+    #[should_panic(expected = "stale checkpoint")]
+    fn test_rollback_after_clear_history() {
+        let mut grid = Grid::<char>::parse("...").unwrap().track_changes();
+        let checkpoint = grid.checkpoint();
+        grid.clear_history();
+        grid.rollback(checkpoint);
+    }
+
+    #[test]
+    #[should_panic(expected = "stale checkpoint")]
+    fn test_rollback_past_later_checkpoint() {
+        let mut grid = Grid::<char>::parse("...").unwrap().track_changes();
+        let a = grid.checkpoint();
+        grid[GridVec::from([0, 0])] = '#';
+        let b = grid.checkpoint();
+        grid.rollback(a);
+        grid[GridVec::from([0, 1])] = '#';
+        grid.rollback(b);
+    }
+
+    #[test]
+    fn test_rollback_to_same_checkpoint_twice() {
+        let mut grid = Grid::<char>::parse("...").unwrap().track_changes();
+        let checkpoint = grid.checkpoint();
+        grid[GridVec::from([0, 0])] = '#';
+        grid.rollback(checkpoint);
+        grid[GridVec::from([0, 1])] = '#';
+        grid.rollback(checkpoint);
+        assert_eq!(grid.rows, Grid::<char>::parse("...").unwrap().rows);
+    }
+
+    #[test]
+    fn test_format_dirty() {
+        let mut grid = Grid::<char>::parse("..\n..").unwrap().track_changes();
+        grid[GridVec::from([1, 0])] = '#';
+
+        assert_eq!(grid.format_dirty(), "\x1b[2;1H#\x1b[3;1H");
+        assert_eq!(grid.format_dirty(), "\x1b[3;1H");
+    }
+}
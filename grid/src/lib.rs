@@ -4,13 +4,58 @@ use std::cmp::{PartialOrd, Ord};
 use std::hash::Hash;
 use derive_more::Display;
 
+pub mod array_grid;
+pub mod automaton;
+pub mod bytes;
+pub mod cycle;
+pub mod dag;
+pub mod diff;
+pub mod distance;
+pub mod frames;
+#[cfg(feature = "petgraph")]
+pub mod graph;
+pub mod grid_map;
+#[cfg(feature = "tui")]
+pub mod inspect;
+pub mod journal;
+#[cfg(feature = "rayon")]
+pub mod par;
+pub mod point;
+pub mod push;
+pub mod radius;
+pub mod ray_index;
+pub mod regions;
+pub mod search;
+#[cfg(feature = "serde")]
+pub mod serde_support;
+pub mod summed_area;
+pub mod symmetry;
+pub mod transform;
+pub mod viewport;
+
+#[cfg(test)]
+mod test_util;
+
+pub use grid_map::GridMap;
+pub use point::Point;
+pub use summed_area::SummedArea;
+#[cfg(feature = "derive")]
+pub use grid_derive::GridCell;
+
 const ANSI_RESET: &str = "\x1b[0m";
 const ANSI_BRIGHT_BG: &str = "\x1b[1;47m\x1b[1;30m";
+const ANSI_BG_RED: &str = "\x1b[41m";
+const ANSI_BG_GREEN: &str = "\x1b[42m";
 
 // Error / Result
 #[derive(Display, Debug)]
 pub enum Error {
-    InconsitantRowLengths
+    InconsitantRowLengths,
+    #[display("InvalidCell({_0:?})")]
+    InvalidCell(char),
+    InvalidFrameData,
+    NotAcyclic,
+    WrongDimensions
 }
 
 impl std::error::Error for Error {}
@@ -142,7 +187,108 @@ impl SubAssign for GridVec {
     }
 }
 
+// Rect
+/// A rectangle of cells spanning from `min` (inclusive) to `max` (exclusive)
+#[derive(Clone, Copy, Hash, PartialEq, Eq, Default, Debug)]
+pub struct Rect {
+    pub min: GridVec,
+    pub max: GridVec
+}
+
+impl Rect {
+    pub fn new(min: GridVec, max: GridVec) -> Self {
+        Rect { min, max }
+    }
+
+    pub fn row_count(&self) -> usize {
+        (self.max.i - self.min.i).max(0) as usize
+    }
+
+    pub fn col_count(&self) -> usize {
+        (self.max.j - self.min.j).max(0) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.row_count() == 0 || self.col_count() == 0
+    }
+
+    pub fn contains(&self, loc: GridVec) -> bool {
+        loc.i >= self.min.i
+            && loc.j >= self.min.j
+            && loc.i < self.max.i
+            && loc.j < self.max.j
+    }
+
+    /// Returns the part of `self` which is also inside `other`
+    pub fn intersect(&self, other: &Rect) -> Rect {
+        Rect {
+            min: GridVec { i: self.min.i.max(other.min.i), j: self.min.j.max(other.min.j) },
+            max: GridVec { i: self.max.i.min(other.max.i), j: self.max.j.min(other.max.j) }
+        }
+    }
+}
+
+// Neighborhood
+/// The set of offsets considered "adjacent" to a cell
+#[derive(Clone, Debug)]
+pub enum Neighborhood {
+    /// The 4 orthogonally adjacent cells
+    VonNeumann,
+    /// The 8 orthogonally or diagonally adjacent cells
+    Moore,
+    /// An arbitrary list of offsets
+    Custom(Vec<GridVec>)
+}
+
+const VON_NEUMANN_OFFSETS: [GridVec; 4] = [
+    GridVec { i: -1, j:  0 },
+    GridVec { i:  0, j:  1 },
+    GridVec { i:  1, j:  0 },
+    GridVec { i:  0, j: -1 },
+];
+
+const MOORE_OFFSETS: [GridVec; 8] = [
+    GridVec { i: -1, j: -1 },
+    GridVec { i: -1, j:  0 },
+    GridVec { i: -1, j:  1 },
+    GridVec { i:  0, j: -1 },
+    GridVec { i:  0, j:  1 },
+    GridVec { i:  1, j: -1 },
+    GridVec { i:  1, j:  0 },
+    GridVec { i:  1, j:  1 },
+];
+
+impl Neighborhood {
+    /// Returns the offsets making up this neighborhood
+    pub fn offsets(&self) -> &[GridVec] {
+        match self {
+            Neighborhood::VonNeumann => &VON_NEUMANN_OFFSETS,
+            Neighborhood::Moore => &MOORE_OFFSETS,
+            Neighborhood::Custom(offsets) => offsets
+        }
+    }
+}
+
+// AsChar
+/// Cell types which can be drawn as a single character
+pub trait AsChar {
+    fn as_char(&self) -> char;
+}
+
+impl AsChar for char {
+    fn as_char(&self) -> char {
+        *self
+    }
+}
+
+impl AsChar for u8 {
+    fn as_char(&self) -> char {
+        *self as char
+    }
+}
+
 // Grid
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct Grid<T> {
     pub rows: Vec<Vec<T>>,
     pub row_count: usize,
@@ -150,6 +296,7 @@ pub struct Grid<T> {
 }
 
 impl<T> Grid<T> {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_iter(row_it: impl Iterator<Item = impl Iterator<Item = T>>) -> Result<Self> {
         let mut grid = Grid {
             rows: Vec::new(),
@@ -201,25 +348,56 @@ impl<T> Grid<T> {
         Some(&mut self.rows[loc.i as usize][loc.j as usize])
     }
 
+    /// Returns the rectangle covering every cell of the grid
+    pub fn bounds(&self) -> Rect {
+        Rect::new(GridVec::default(), GridVec::from([self.row_count, self.col_count]))
+    }
+
+    /// Iterates over the location of every cell in row-major order
+    pub fn locs(&self) -> impl Iterator<Item = GridVec> + use<T> {
+        let col_count = self.col_count;
+        (0..self.row_count).flat_map(move |i| (0..col_count).map(move |j| GridVec::from([i, j])))
+    }
+
+    /// Returns the locations adjacent to `loc` (according to `neighborhood`) which are on the grid
+    pub fn neighbors<'a>(&'a self, loc: GridVec, neighborhood: &'a Neighborhood) -> impl Iterator<Item = GridVec> + 'a {
+        neighborhood.offsets()
+            .iter()
+            .map(move |&dir| loc + dir)
+            .filter(|&adj_loc| self.get(adj_loc).is_some())
+    }
 }
 
-impl Grid<char> {
-    pub fn render(&self) {
-        let highlights: BTreeSet<GridVec> = BTreeSet::new();
-        for i in 0..self.row_count {
-            for j in 0..self.col_count {
-                let loc = GridVec::from([i, j]);
-                let hl = highlights.contains(&loc);
-                if hl {
-                    print!("{}", ANSI_BRIGHT_BG);
-                }
-                print!("{}", self[loc]);
-                if hl {
-                    print!("{}", ANSI_RESET);
-                }
-            }
-            println!();
+impl<T: Clone> Grid<T> {
+    /// Creates a `row_count` x `col_count` grid with every cell set to `fill`
+    pub fn new(row_count: usize, col_count: usize, fill: T) -> Self {
+        Grid {
+            rows: vec![vec![fill; col_count]; row_count],
+            row_count,
+            col_count
+        }
+    }
+}
+
+impl<T: TryFrom<char>> Grid<T> {
+    /// Parses one row per line of `input`, converting each char to a cell. Lines are taken as they
+    /// are, so whitespace counts as cells.
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut rows = vec![];
+        for line in input.lines() {
+            let row = line.chars()
+                .map(|c| T::try_from(c).map_err(|_| Error::InvalidCell(c)))
+                .collect::<Result<Vec<_>>>()?;
+            rows.push(row);
         }
+
+        Grid::from_iter(rows.into_iter().map(|row| row.into_iter()))
+    }
+}
+
+impl<T: AsChar> Grid<T> {
+    pub fn render(&self) {
+        self.render_with_highlights(&BTreeSet::new());
     }
 
     pub fn render_with_highlights(&self, highlights: &BTreeSet<GridVec>) {
@@ -230,7 +408,7 @@ impl Grid<char> {
                 if hl {
                     print!("{}", ANSI_BRIGHT_BG);
                 }
-                print!("{}", self[loc]);
+                print!("{}", self[loc].as_char());
                 if hl {
                     print!("{}", ANSI_RESET);
                 }
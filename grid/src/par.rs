@@ -0,0 +1,67 @@
+//! Parallel iteration over a `Grid`, enabled by the `rayon` feature
+
+use rayon::prelude::*;
+
+use crate::{Grid, GridVec};
+
+impl<T: Sync> Grid<T> {
+    /// Parallel iterator over every cell
+    pub fn par_iter(&self) -> impl ParallelIterator<Item = &T> {
+        self.rows.par_iter().flat_map(|row| row.par_iter())
+    }
+
+    /// Parallel iterator over every cell along with its location
+    pub fn par_enumerate(&self) -> impl ParallelIterator<Item = (GridVec, &T)> {
+        self.rows.par_iter()
+            .enumerate()
+            .flat_map(|(i, row)| row.par_iter()
+                .enumerate()
+                .map(move |(j, c)| (GridVec::from([i, j]), c))
+            )
+    }
+
+    /// Parallel iterator over the rows, top to bottom
+    pub fn par_rows(&self) -> impl IndexedParallelIterator<Item = &[T]> {
+        self.rows.par_iter().map(|row| row.as_slice())
+    }
+
+    /// Builds a new grid of the same size by calling `f` on every cell in parallel
+    pub fn par_map<U, F>(&self, f: F) -> Grid<U>
+    where
+        U: Send,
+        F: Fn(GridVec, &T) -> U + Sync + Send
+    {
+        let rows = self.rows.par_iter()
+            .enumerate()
+            .map(|(i, row)| row.iter()
+                .enumerate()
+                .map(|(j, c)| f(GridVec::from([i, j]), c))
+                .collect()
+            )
+            .collect();
+
+        Grid {
+            rows,
+            row_count: self.row_count,
+            col_count: self.col_count
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_par_matches_sequential() {
+        let grid = Grid::from_iter((0..20).map(|i| (0..30).map(move |j| i * 30 + j))).unwrap();
+
+        assert_eq!(grid.par_iter().sum::<i32>(), (0..600).sum::<i32>());
+        assert_eq!(grid.par_rows().map(|row| row.len()).sum::<usize>(), 600);
+        assert!(grid.par_enumerate().all(|(loc, &c)| c as i64 == loc.i * 30 + loc.j));
+
+        let doubled = grid.par_map(|loc, &c| (loc, 2 * c));
+        assert_eq!(doubled[GridVec::from([3, 4])], (GridVec::from([3, 4]), 2 * 94));
+        assert_eq!((doubled.row_count, doubled.col_count), (20, 30));
+    }
+}
@@ -0,0 +1,98 @@
+use crate::GridVec;
+
+/// A location in puzzle-style `(x, y)` coordinates, where `x` counts columns to the right and `y`
+/// counts rows downwards. This is the transpose of `GridVec`'s `(i, j)`, so converting between the
+/// two swaps the components: `Point { x, y }` is `GridVec { i: y, j: x }`.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Default, Debug)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64
+}
+
+impl Point {
+    pub fn new(x: i64, y: i64) -> Self {
+        Point { x, y }
+    }
+
+    /// Parses the first `X` and `Y` values out of text such as `Prize: X=8400, Y=5400` or
+    /// `Button A: X+94, Y+34`. Each label must be followed by `=` or `+` and then the number.
+    pub fn parse_xy(s: &str) -> Option<Point> {
+        Some(Point {
+            x: parse_labeled_int(s, 'X')?,
+            y: parse_labeled_int(s, 'Y')?
+        })
+    }
+
+    /// Parses a `label=x,y` pair, such as the velocity in `p=0,4 v=3,-3` with label `v`
+    pub fn parse_pair(s: &str, label: &str) -> Option<Point> {
+        let prefix = format!("{}=", label);
+        let (start, _) = s.match_indices(&prefix)
+            .find(|&(k, _)| !s[..k].ends_with(|c: char| c.is_alphanumeric()))?;
+
+        let rest = &s[start + prefix.len()..];
+        let (x, rest) = split_int(rest)?;
+        let (y, _) = split_int(rest.strip_prefix(',')?)?;
+
+        Some(Point { x, y })
+    }
+}
+
+impl From<Point> for GridVec {
+    fn from(p: Point) -> Self {
+        GridVec { i: p.y, j: p.x }
+    }
+}
+
+impl From<GridVec> for Point {
+    fn from(v: GridVec) -> Self {
+        Point { x: v.j, y: v.i }
+    }
+}
+
+/// Finds `label` followed by `=` or `+` and parses the integer after it
+fn parse_labeled_int(s: &str, label: char) -> Option<i64> {
+    s.match_indices(label)
+        .filter_map(|(k, _)| s[k + 1..].strip_prefix(['=', '+']))
+        .find_map(|rest| split_int(rest).map(|(n, _)| n))
+}
+
+/// Parses an optionally negative integer off the front of `s`, returning it along with the rest of `s`
+fn split_int(s: &str) -> Option<(i64, &str)> {
+    let digits_start = if s.starts_with('-') { 1 } else { 0 };
+    let end = s[digits_start..]
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(s.len(), |k| k + digits_start);
+
+    Some((s[..end].parse().ok()?, &s[end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        let p = Point::new(3, 7);
+        let v = GridVec::from(p);
+        assert_eq!(v, GridVec { i: 7, j: 3 });
+        assert_eq!(Point::from(v), p);
+        assert_eq!(Point::from(GridVec::from([1, -2])), Point { x: -2, y: 1 });
+    }
+
+    #[test]
+    fn test_parse_xy() {
+        assert_eq!(Point::parse_xy("Button A: X+94, Y+34"), Some(Point::new(94, 34)));
+        assert_eq!(Point::parse_xy("Prize: X=8400, Y=5400"), Some(Point::new(8400, 5400)));
+        assert_eq!(Point::parse_xy("X=-1, Y=2"), Some(Point::new(-1, 2)));
+        assert_eq!(Point::parse_xy("X=1"), None);
+    }
+
+    #[test]
+    fn test_parse_pair() {
+        let line = "p=0,4 v=3,-3";
+        assert_eq!(Point::parse_pair(line, "p"), Some(Point::new(0, 4)));
+        assert_eq!(Point::parse_pair(line, "v"), Some(Point::new(3, -3)));
+        assert_eq!(Point::parse_pair("vp=1,1", "p"), None);
+        assert_eq!(Point::parse_pair("p=1;2", "p"), None);
+    }
+}
@@ -0,0 +1,186 @@
+use std::borrow::Borrow;
+use std::collections::{BTreeSet, VecDeque};
+use std::ops::IndexMut;
+
+use crate::{Grid, GridVec};
+
+/// A rigid object made up of one or more cells. Each cell is given as an offset from the object's
+/// anchor along with the value that cell holds on the grid.
+#[derive(Clone, Debug)]
+pub struct Shape<T> {
+    pub cells: Vec<(GridVec, T)>
+}
+
+impl<T> Shape<T> {
+    /// A single-cell object
+    pub fn single(value: T) -> Self {
+        Shape { cells: vec![(GridVec::default(), value)] }
+    }
+
+    /// An object laid out the way it appears on the grid, with the anchor at the top-left cell.
+    /// For example, `Shape::from_rows([['[', ']']])` is a box two cells wide.
+    pub fn from_rows(rows: impl IntoIterator<Item = impl IntoIterator<Item = T>>) -> Self {
+        let cells = rows.into_iter()
+            .enumerate()
+            .flat_map(|(i, row)| row.into_iter()
+                .enumerate()
+                .map(move |(j, value)| (GridVec::from([i, j]), value))
+            )
+            .collect();
+
+        Shape { cells }
+    }
+}
+
+/// Result of trying to push the object at some location
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Push {
+    /// The push can happen. Holds every cell which moves, furthest along the push direction first,
+    /// so moving them in order never overwrites a cell which has yet to move.
+    Clear(Vec<GridVec>),
+    /// The push can't happen because of the cell at this location
+    Blocked(GridVec)
+}
+
+/// Works out which objects move when one is pushed, Sokoban-style. Cells holding `empty` can be
+/// pushed into. Cells which are part of one of `shapes` move along when pushed. Anything else
+/// (including the edge of the grid) blocks the push.
+#[derive(Clone, Debug)]
+pub struct PushEngine<T> {
+    pub empty: T,
+    pub shapes: Vec<Shape<T>>
+}
+
+impl<T: PartialEq> PushEngine<T> {
+    /// Returns the cells making up the object which covers `loc`, if there is one
+    pub fn object_at(&self, grid: &Grid<T>, loc: GridVec) -> Option<Vec<GridVec>> {
+        let value = grid.get(loc)?;
+        for shape in self.shapes.iter() {
+            for (offset, shape_value) in shape.cells.iter() {
+                if shape_value != value {
+                    continue;
+                }
+
+                let anchor = loc - *offset;
+                let fits = shape.cells.iter()
+                    .all(|(o, v)| grid.get(anchor + *o) == Some(v));
+                if fits {
+                    return Some(shape.cells.iter().map(|(o, _)| anchor + *o).collect());
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Finds every cell which has to move for the object at `loc` to move one step in `dir`
+    pub fn cells_to_push(&self, grid: &Grid<T>, loc: GridVec, dir: GridVec) -> Push {
+        match grid.get(loc) {
+            None => return Push::Blocked(loc),
+            Some(value) if *value == self.empty => return Push::Clear(vec![]),
+            _ => {}
+        }
+
+        let mut moving = BTreeSet::new();
+        let mut queue = VecDeque::from([loc]);
+        while let Some(loc) = queue.pop_front() {
+            if moving.contains(&loc) {
+                continue;
+            }
+            let Some(object_locs) = self.object_at(grid, loc) else {
+                return Push::Blocked(loc);
+            };
+            moving.extend(object_locs.iter().copied());
+
+            for next_loc in object_locs.into_iter().map(|l| l + dir) {
+                match grid.get(next_loc) {
+                    None => return Push::Blocked(next_loc),
+                    Some(value) if *value == self.empty => {},
+                    Some(_) => queue.push_back(next_loc)
+                }
+            }
+        }
+
+        let mut cells = moving.into_iter().collect::<Vec<_>>();
+        cells.sort_by_key(|loc| -dir.i * loc.i - dir.j * loc.j);
+
+        Push::Clear(cells)
+    }
+}
+
+impl<T: PartialEq + Clone> PushEngine<T> {
+    /// Pushes the object at `loc` one step in `dir` if nothing blocks it, leaving `empty` behind.
+    /// Works on a plain `Grid` or a `TrackedGrid`.
+    pub fn push<G>(&self, grid: &mut G, loc: GridVec, dir: GridVec) -> Push
+    where
+        G: Borrow<Grid<T>> + IndexMut<GridVec, Output = T>
+    {
+        let push = self.cells_to_push((*grid).borrow(), loc, dir);
+        if let Push::Clear(cells) = &push {
+            for &cell in cells {
+                grid[cell + dir] = std::mem::replace(&mut grid[cell], self.empty.clone());
+            }
+        }
+
+        push
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::parse;
+
+    fn engine() -> PushEngine<char> {
+        PushEngine {
+            empty: '.',
+            shapes: vec![
+                Shape::single('@'),
+                Shape::from_rows([['[', ']']]),
+                Shape::from_rows([['A', 'A'], ['A', 'A']]),
+            ]
+        }
+    }
+
+    #[test]
+    fn test_wide_boxes_push_together() {
+        let mut grid = parse("\
+            .......
+            ..[][].
+            ...[]..
+            ...@...");
+        let push = engine().push(&mut grid, GridVec::from([3, 3]), GridVec::from([-1, 0]));
+
+        assert_eq!(push, Push::Clear(vec![
+            GridVec::from([1, 2]),
+            GridVec::from([1, 3]),
+            GridVec::from([1, 4]),
+            GridVec::from([1, 5]),
+            GridVec::from([2, 3]),
+            GridVec::from([2, 4]),
+            GridVec::from([3, 3]),
+        ]));
+        assert_eq!(grid, parse("\
+            ..[][].
+            ...[]..
+            ...@...
+            ......."));
+    }
+
+    #[test]
+    fn test_blocked_push_changes_nothing() {
+        let mut grid = parse("\
+            ..#..
+            .AA..
+            .AA..
+            ..@..");
+        let before = grid.clone();
+
+        let push = engine().push(&mut grid, GridVec::from([3, 2]), GridVec::from([-1, 0]));
+        assert_eq!(push, Push::Blocked(GridVec::from([0, 2])));
+        assert_eq!(grid, before);
+
+        let push = engine().push(&mut grid, GridVec::from([3, 2]), GridVec::from([0, 1]));
+        assert_eq!(push, Push::Clear(vec![GridVec::from([3, 2])]));
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{Grid, GridVec};
+
+// All of these iterate in row-major order, so results line up with `Grid::locs`.
+impl GridVec {
+    /// Every location at Manhattan distance `r` or less from `self` (a diamond), including `self`
+    pub fn within_manhattan(self, r: u32) -> impl Iterator<Item = GridVec> {
+        let r = r as i64;
+        (-r..=r).flat_map(move |di| {
+            let w = r - di.abs();
+            (-w..=w).map(move |dj| self + GridVec { i: di, j: dj })
+        })
+    }
+
+    /// Every location at Chebyshev distance `r` or less from `self` (a square), including `self`
+    pub fn within_chebyshev(self, r: u32) -> impl Iterator<Item = GridVec> {
+        let r = r as i64;
+        (-r..=r).flat_map(move |di| (-r..=r).map(move |dj| self + GridVec { i: di, j: dj }))
+    }
+
+    /// Every location at exactly Manhattan distance `r` from `self`
+    pub fn manhattan_ring(self, r: u32) -> impl Iterator<Item = GridVec> {
+        let r = r as i64;
+        (-r..=r).flat_map(move |di| {
+            let w = r - di.abs();
+            let dj_count = if w == 0 { 1 } else { 2 };
+            [-w, w].into_iter()
+                .take(dj_count)
+                .map(move |dj| self + GridVec { i: di, j: dj })
+        })
+    }
+
+    /// Every location at exactly Chebyshev distance `r` from `self`
+    pub fn chebyshev_ring(self, r: u32) -> impl Iterator<Item = GridVec> {
+        let r = r as i64;
+        (-r..=r).flat_map(move |di| {
+            // The top and bottom edges are full rows, the rest only have their two ends
+            let step = if di.abs() == r { 1 } else { (2 * r) as usize };
+            (-r..=r).step_by(step.max(1)).map(move |dj| self + GridVec { i: di, j: dj })
+        })
+    }
+}
+
+impl<T> Grid<T> {
+    /// Like `GridVec::within_manhattan`, but only locations on the grid
+    pub fn within_manhattan(&self, loc: GridVec, r: u32) -> impl Iterator<Item = GridVec> + '_ {
+        loc.within_manhattan(r).filter(|&l| self.get(l).is_some())
+    }
+
+    /// Like `GridVec::within_chebyshev`, but only locations on the grid
+    pub fn within_chebyshev(&self, loc: GridVec, r: u32) -> impl Iterator<Item = GridVec> + '_ {
+        loc.within_chebyshev(r).filter(|&l| self.get(l).is_some())
+    }
+
+    /// Like `GridVec::manhattan_ring`, but only locations on the grid
+    pub fn manhattan_ring(&self, loc: GridVec, r: u32) -> impl Iterator<Item = GridVec> + '_ {
+        loc.manhattan_ring(r).filter(|&l| self.get(l).is_some())
+    }
+
+    /// Like `GridVec::chebyshev_ring`, but only locations on the grid
+    pub fn chebyshev_ring(&self, loc: GridVec, r: u32) -> impl Iterator<Item = GridVec> + '_ {
+        loc.chebyshev_ring(r).filter(|&l| self.get(l).is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manhattan_dist(a: GridVec, b: GridVec) -> i64 {
+        (a.i - b.i).abs() + (a.j - b.j).abs()
+    }
+
+    fn chebyshev_dist(a: GridVec, b: GridVec) -> i64 {
+        (a.i - b.i).abs().max((a.j - b.j).abs())
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let center = GridVec::from([2, -3]);
+        for r in 0..4u32 {
+            let square = center.within_chebyshev(r + 1).collect::<Vec<_>>();
+            let brute = |dist: fn(GridVec, GridVec) -> i64, exact: bool| square.iter()
+                .copied()
+                .filter(|&l| if exact { dist(l, center) == r as i64 } else { dist(l, center) <= r as i64 })
+                .collect::<Vec<_>>();
+
+            assert_eq!(center.within_manhattan(r).collect::<Vec<_>>(), brute(manhattan_dist, false));
+            assert_eq!(center.within_chebyshev(r).collect::<Vec<_>>(), brute(chebyshev_dist, false));
+            assert_eq!(center.manhattan_ring(r).collect::<Vec<_>>(), brute(manhattan_dist, true));
+            assert_eq!(center.chebyshev_ring(r).collect::<Vec<_>>(), brute(chebyshev_dist, true));
+        }
+    }
+
+    #[test]
+    fn test_grid_bounded() {
+        let grid = Grid::new(3, 4, '.');
+        let corner = GridVec::default();
+        assert_eq!(grid.within_manhattan(corner, 2).count(), 6);
+        assert_eq!(grid.within_chebyshev(corner, 1).count(), 4);
+        assert_eq!(grid.manhattan_ring(corner, 1).collect::<Vec<_>>(), vec![GridVec::from([0, 1]), GridVec::from([1, 0])]);
+        assert_eq!(grid.chebyshev_ring(GridVec::from([1, 1]), 1).count(), 8);
+    }
+}
@@ -0,0 +1,115 @@
+use crate::{Grid, GridVec};
+
+/// Sorted obstacle positions along every row and column, for simulations which walk in a straight
+/// line until something is in the way. Finding the next obstacle is a binary search rather than a
+/// step-by-step walk.
+#[derive(Clone, Debug)]
+pub struct RayIndex {
+    pub row_count: usize,
+    pub col_count: usize,
+    /// Column of every obstacle in each row, ascending
+    rows: Vec<Vec<i64>>,
+    /// Row of every obstacle in each column, ascending
+    cols: Vec<Vec<i64>>,
+    /// One extra obstacle which can be moved around without rebuilding the index
+    pub temporary: Option<GridVec>
+}
+
+impl RayIndex {
+    /// Indexes `obstacles` on a `row_count` x `col_count` grid. Obstacles off the grid are ignored.
+    pub fn new(row_count: usize, col_count: usize, obstacles: impl IntoIterator<Item = GridVec>) -> Self {
+        let mut rows = vec![vec![]; row_count];
+        let mut cols = vec![vec![]; col_count];
+        for loc in obstacles {
+            if loc.i >= 0 && loc.j >= 0 && (loc.i as usize) < row_count && (loc.j as usize) < col_count {
+                rows[loc.i as usize].push(loc.j);
+                cols[loc.j as usize].push(loc.i);
+            }
+        }
+        for line in rows.iter_mut().chain(cols.iter_mut()) {
+            line.sort_unstable();
+            line.dedup();
+        }
+
+        RayIndex { row_count, col_count, rows, cols, temporary: None }
+    }
+
+    /// Indexes every cell of `grid` for which `is_obstacle` returns true
+    pub fn from_grid<T>(grid: &Grid<T>, is_obstacle: impl Fn(&T) -> bool) -> Self {
+        let obstacles = grid.locs().filter(|&loc| is_obstacle(&grid[loc]));
+        RayIndex::new(grid.row_count, grid.col_count, obstacles)
+    }
+
+    pub fn is_blocked(&self, loc: GridVec) -> bool {
+        self.temporary == Some(loc)
+            || self.line(loc.i, &self.rows).is_some_and(|row| row.binary_search(&loc.j).is_ok())
+    }
+
+    /// Finds the nearest obstacle strictly beyond `loc` in direction `dir`, which must be one of
+    /// the four unit directions. Returns `None` if the ray leaves the grid without hitting anything.
+    pub fn next_blocking(&self, loc: GridVec, dir: GridVec) -> Option<GridVec> {
+        let hit = match (dir.i, dir.j) {
+            (0, 1) => next_after(self.line(loc.i, &self.rows)?, loc.j).map(|j| GridVec { i: loc.i, j }),
+            (0, -1) => next_before(self.line(loc.i, &self.rows)?, loc.j).map(|j| GridVec { i: loc.i, j }),
+            (1, 0) => next_after(self.line(loc.j, &self.cols)?, loc.i).map(|i| GridVec { i, j: loc.j }),
+            (-1, 0) => next_before(self.line(loc.j, &self.cols)?, loc.i).map(|i| GridVec { i, j: loc.j }),
+            _ => panic!("{:?} is not a unit direction", dir)
+        };
+
+        // The temporary obstacle wins if it's on the ray and closer than the indexed hit
+        let dist = |target: GridVec| (target - loc).i * dir.i + (target - loc).j * dir.j;
+        let temporary = self.temporary.filter(|&t| {
+            let offset = t - loc;
+            offset.i * dir.j == offset.j * dir.i && dist(t) > 0
+        });
+        match (hit, temporary) {
+            (Some(h), Some(t)) => Some(if dist(t) < dist(h) { t } else { h }),
+            (h, t) => h.or(t)
+        }
+    }
+
+    fn line<'a>(&self, k: i64, lines: &'a [Vec<i64>]) -> Option<&'a Vec<i64>> {
+        usize::try_from(k).ok().and_then(|k| lines.get(k))
+    }
+}
+
+fn next_after(line: &[i64], pos: i64) -> Option<i64> {
+    line.get(line.partition_point(|&p| p <= pos)).copied()
+}
+
+fn next_before(line: &[i64], pos: i64) -> Option<i64> {
+    match line.partition_point(|&p| p < pos) {
+        0 => None,
+        k => Some(line[k - 1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::parse;
+
+    #[test]
+    fn test_next_blocking() {
+        let grid = parse("\
+            ..#..
+            .....
+            #...#
+            .....");
+        let mut rays = RayIndex::from_grid(&grid, |&c| c == '#');
+        let center = GridVec::from([2, 2]);
+
+        assert_eq!(rays.next_blocking(center, GridVec::from([0, 1])), Some(GridVec::from([2, 4])));
+        assert_eq!(rays.next_blocking(center, GridVec::from([0, -1])), Some(GridVec::from([2, 0])));
+        assert_eq!(rays.next_blocking(center, GridVec::from([-1, 0])), Some(GridVec::from([0, 2])));
+        assert_eq!(rays.next_blocking(center, GridVec::from([1, 0])), None);
+        assert!(rays.is_blocked(GridVec::from([2, 4])));
+
+        rays.temporary = Some(GridVec::from([2, 3]));
+        assert_eq!(rays.next_blocking(center, GridVec::from([0, 1])), Some(GridVec::from([2, 3])));
+        assert_eq!(rays.next_blocking(GridVec::from([2, 4]), GridVec::from([0, 1])), None);
+        rays.temporary = Some(GridVec::from([3, 2]));
+        assert_eq!(rays.next_blocking(center, GridVec::from([1, 0])), Some(GridVec::from([3, 2])));
+        assert_eq!(rays.next_blocking(GridVec::from([3, 0]), GridVec::from([0, 1])), Some(GridVec::from([3, 2])));
+    }
+}
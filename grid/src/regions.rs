@@ -0,0 +1,217 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt::Write;
+
+use crate::{Grid, GridVec, Neighborhood};
+
+/// A group of orthogonally connected cells which all hold the same value
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Region {
+    pub cells: BTreeSet<GridVec>
+}
+
+/// A closed loop along the lines between cells. Vertex `(i, j)` is the top-left corner of cell
+/// `(i, j)`. Vertices are only placed where the boundary turns, and the loop runs clockwise (as drawn
+/// on screen) around the region for outer boundaries, and anti-clockwise for holes.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Polygon {
+    pub vertices: Vec<GridVec>
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// Splits the grid up into regions of equal, orthogonally connected cells
+    pub fn regions(&self) -> Vec<Region> {
+        let mut seen = Grid::new(self.row_count, self.col_count, false);
+        let mut regions = vec![];
+        for start in self.locs() {
+            if seen[start] {
+                continue;
+            }
+
+            seen[start] = true;
+            let mut cells = BTreeSet::new();
+            let mut queue = VecDeque::from([start]);
+            while let Some(loc) = queue.pop_front() {
+                cells.insert(loc);
+                for adj_loc in self.neighbors(loc, &Neighborhood::VonNeumann) {
+                    if !seen[adj_loc] && self[adj_loc] == self[start] {
+                        seen[adj_loc] = true;
+                        queue.push_back(adj_loc);
+                    }
+                }
+            }
+            regions.push(Region { cells });
+        }
+
+        regions
+    }
+}
+
+impl Region {
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Number of cell edges between this region and the outside world
+    pub fn perimeter(&self) -> usize {
+        self.boundary_edges().values().map(|dirs| dirs.len()).sum()
+    }
+
+    /// Total number of straight sides across the outer boundary and all holes
+    pub fn side_count(&self) -> usize {
+        self.boundaries().iter().map(|p| p.side_count()).sum()
+    }
+
+    /// Traces the outer boundary and the boundary of every hole into polygons. Where two cells of a
+    /// region (or two cells of a hole) only touch at a corner, the boundary is split into two loops
+    /// at that corner, rather than one loop which crosses itself.
+    pub fn boundaries(&self) -> Vec<Polygon> {
+        let edges = self.boundary_edges();
+
+        let mut used = BTreeSet::new();
+        let mut polygons = vec![];
+        for (&first_vertex, dirs) in edges.iter() {
+            for &first_dir in dirs.iter() {
+                if used.contains(&(first_vertex, first_dir)) {
+                    continue;
+                }
+
+                // Walk with the region on our right, turning right whenever we can so that
+                // boundaries which touch at a corner stay separate
+                let mut loop_dirs = vec![];
+                let (mut vertex, mut dir) = (first_vertex, first_dir);
+                loop {
+                    used.insert((vertex, dir));
+                    loop_dirs.push((vertex, dir));
+                    vertex += dir;
+                    let options = &edges[&vertex];
+                    dir = [dir.rot_90_anti(), dir, dir.rot_90()].into_iter()
+                        .find(|next_dir| options.contains(next_dir))
+                        .unwrap();
+                    if (vertex, dir) == (first_vertex, first_dir) {
+                        break;
+                    }
+                }
+
+                // Keep only the vertices where the boundary turns
+                let vertices = (0..loop_dirs.len())
+                    .filter(|&k| {
+                        let prev_dir = loop_dirs[(k + loop_dirs.len() - 1) % loop_dirs.len()].1;
+                        prev_dir != loop_dirs[k].1
+                    })
+                    .map(|k| loop_dirs[k].0)
+                    .collect();
+                polygons.push(Polygon { vertices });
+            }
+        }
+
+        polygons
+    }
+
+    /// Maps each corner vertex to the directions of the boundary edges leaving it. Edges run
+    /// clockwise (on screen) around each cell, and edges shared by two region cells are left out.
+    fn boundary_edges(&self) -> BTreeMap<GridVec, Vec<GridVec>> {
+        let mut edges: BTreeMap<GridVec, Vec<GridVec>> = BTreeMap::new();
+        for &loc in self.cells.iter() {
+            // (neighbor direction, edge start relative to `loc`, edge direction)
+            let sides = [
+                ([-1, 0], [0, 0], [ 0, 1]),
+                ([ 0, 1], [0, 1], [ 1, 0]),
+                ([ 1, 0], [1, 1], [ 0,-1]),
+                ([ 0,-1], [1, 0], [-1, 0]),
+            ];
+            for (adj_dir, start, edge_dir) in sides {
+                if !self.cells.contains(&(loc + GridVec::from(adj_dir))) {
+                    edges.entry(loc + GridVec::from(start))
+                        .or_default()
+                        .push(GridVec::from(edge_dir));
+                }
+            }
+        }
+
+        edges
+    }
+}
+
+impl Polygon {
+    pub fn side_count(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// Signed area enclosed by the polygon (via the shoelace formula). Positive for outer
+    /// boundaries and negative for holes.
+    pub fn area(&self) -> i64 {
+        let n = self.vertices.len();
+        let twice_area: i64 = (0..n)
+            .map(|k| {
+                let (a, b) = (self.vertices[k], self.vertices[(k + 1) % n]);
+                a.j * b.i - b.j * a.i
+            })
+            .sum();
+
+        twice_area / 2
+    }
+
+    pub fn is_hole(&self) -> bool {
+        self.area() < 0
+    }
+
+    /// Formats the polygon as SVG path data, with `x` as the column and `y` as the row
+    pub fn to_svg_path(&self) -> String {
+        let mut path = String::new();
+        for (k, v) in self.vertices.iter().enumerate() {
+            let cmd = if k == 0 { 'M' } else { 'L' };
+            write!(path, "{}{} {} ", cmd, v.j, v.i).unwrap();
+        }
+        path.push('Z');
+
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::parse;
+
+    fn fence_price(s: &str) -> usize {
+        parse(s).regions().iter().map(|r| r.area() * r.side_count()).sum()
+    }
+
+    #[test]
+    fn test_square() {
+        let grid = parse("..\n.#");
+        let regions = grid.regions();
+        assert_eq!(regions.len(), 2);
+
+        let boundaries = regions[1].boundaries();
+        assert_eq!(boundaries, vec![Polygon { vertices: vec![
+            GridVec::from([1, 1]),
+            GridVec::from([1, 2]),
+            GridVec::from([2, 2]),
+            GridVec::from([2, 1]),
+        ]}]);
+        assert_eq!(boundaries[0].area(), 1);
+        assert_eq!(boundaries[0].to_svg_path(), "M1 1 L2 1 L2 2 L1 2 Z");
+        assert_eq!(regions[0].perimeter(), 8);
+        assert_eq!(regions[0].side_count(), 6);
+    }
+
+    #[test]
+    fn test_holes() {
+        let grid = parse("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO");
+        let outer = &grid.regions()[0];
+        let boundaries = outer.boundaries();
+
+        assert_eq!(boundaries.len(), 5);
+        assert_eq!(boundaries.iter().filter(|p| p.is_hole()).count(), 4);
+        assert_eq!(boundaries.iter().map(|p| p.area()).sum::<i64>(), outer.area() as i64);
+    }
+
+    #[test]
+    fn test_side_counts() {
+        assert_eq!(fence_price("AAAA\nBBCD\nBBCC\nEEEC"), 80);
+        assert_eq!(fence_price("EEEEE\nEXXXX\nEEEEE\nEXXXX\nEEEEE"), 236);
+        // The two B regions touch at a corner, so the A region has two separate holes
+        assert_eq!(fence_price("AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA"), 368);
+    }
+}
@@ -0,0 +1,266 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::{Grid, GridVec};
+
+/// The outcome of a successful search: the cheapest cost to reach a goal, plus enough bookkeeping
+/// to recover every path which ties for that cost
+pub struct Optimal<S> {
+    pub cost: u64,
+    states: Vec<S>,
+    /// For each state (by index into `states`), the states it can be reached from at optimal cost
+    predecessors: Vec<Vec<usize>>,
+    goals: Vec<usize>
+}
+
+impl<S: Clone + Hash + Eq> Optimal<S> {
+    /// Goal states which can be reached at optimal cost
+    pub fn goals(&self) -> impl Iterator<Item = &S> {
+        self.goals.iter().map(|&k| &self.states[k])
+    }
+
+    /// Every optimal path, from start to goal. The number of paths can grow exponentially, so prefer
+    /// `states` when only the states along the paths matter.
+    pub fn paths(&self) -> Vec<Vec<S>> {
+        let mut paths = vec![];
+        for &goal in self.goals.iter() {
+            let mut stack = vec![vec![goal]];
+            while let Some(partial) = stack.pop() {
+                let preds = &self.predecessors[*partial.last().unwrap()];
+                if preds.is_empty() {
+                    paths.push(partial.iter().rev().map(|&k| self.states[k].clone()).collect());
+                    continue;
+                }
+                for &pred in preds {
+                    let mut extended = partial.clone();
+                    extended.push(pred);
+                    stack.push(extended);
+                }
+            }
+        }
+
+        paths
+    }
+
+    /// Every state which lies on at least one optimal path
+    pub fn states(&self) -> HashSet<S> {
+        let mut seen = HashSet::new();
+        let mut stack = self.goals.clone();
+        while let Some(k) = stack.pop() {
+            if seen.insert(k) {
+                stack.extend(self.predecessors[k].iter().copied());
+            }
+        }
+
+        seen.into_iter().map(|k| self.states[k].clone()).collect()
+    }
+}
+
+/// Finds the cheapest way from any of `starts` to a state where `is_goal` is true, keeping track of
+/// all paths which tie. `successors` lists the states reachable from a state along with the cost of
+/// getting there. Returns `None` if no goal can be reached.
+pub fn dijkstra<S, I, FS, FG>(starts: impl IntoIterator<Item = S>, successors: FS, is_goal: FG) -> Option<Optimal<S>>
+where
+    S: Clone + Hash + Eq,
+    I: IntoIterator<Item = (S, u64)>,
+    FS: FnMut(&S) -> I,
+    FG: FnMut(&S) -> bool
+{
+    astar(starts, successors, |_| 0, is_goal)
+}
+
+/// Same as `dijkstra`, but explores states in order of cost so far plus `heuristic`. The heuristic
+/// must never overestimate the remaining cost to a goal, and must be consistent (it can't drop by
+/// more than the cost of a step), otherwise tied paths may be missed.
+pub fn astar<S, I, FS, FH, FG>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: FS,
+    mut heuristic: FH,
+    mut is_goal: FG
+) -> Option<Optimal<S>>
+where
+    S: Clone + Hash + Eq,
+    I: IntoIterator<Item = (S, u64)>,
+    FS: FnMut(&S) -> I,
+    FH: FnMut(&S) -> u64,
+    FG: FnMut(&S) -> bool
+{
+    let mut states = vec![];
+    let mut index = HashMap::new();
+    let mut costs: Vec<u64> = vec![];
+    let mut predecessors: Vec<Vec<usize>> = vec![];
+    let mut done = vec![];
+    let mut heap = BinaryHeap::new();
+
+    for start in starts {
+        if index.contains_key(&start) {
+            continue;
+        }
+        let k = states.len();
+        heap.push(Reverse((heuristic(&start), 0, k)));
+        index.insert(start.clone(), k);
+        states.push(start);
+        costs.push(0);
+        predecessors.push(vec![]);
+        done.push(false);
+    }
+
+    let mut best_cost = None;
+    let mut goals = vec![];
+    while let Some(Reverse((estimate, cost, k))) = heap.pop() {
+        if best_cost.is_some_and(|best| estimate > best) {
+            break;
+        }
+        if done[k] || cost > costs[k] {
+            continue;
+        }
+        done[k] = true;
+
+        if is_goal(&states[k]) {
+            best_cost = Some(cost);
+            goals.push(k);
+            continue;
+        }
+
+        for (next, step_cost) in successors(&states[k]) {
+            let next_cost = cost + step_cost;
+            let next_k = match index.get(&next) {
+                Some(&next_k) => next_k,
+                None => {
+                    let next_k = states.len();
+                    index.insert(next.clone(), next_k);
+                    states.push(next);
+                    costs.push(u64::MAX);
+                    predecessors.push(vec![]);
+                    done.push(false);
+                    next_k
+                }
+            };
+
+            if next_cost < costs[next_k] {
+                costs[next_k] = next_cost;
+                predecessors[next_k] = vec![k];
+                heap.push(Reverse((next_cost + heuristic(&states[next_k]), next_cost, next_k)));
+            } else if next_cost == costs[next_k] && !done[next_k] {
+                predecessors[next_k].push(k);
+            }
+        }
+    }
+
+    best_cost.map(|cost| Optimal { cost, states, predecessors, goals })
+}
+
+/// A position plus the direction it faces, for searches where turning matters
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Default, Debug)]
+pub struct Pose {
+    pub loc: GridVec,
+    pub dir: GridVec
+}
+
+impl Pose {
+    pub fn forward(&self) -> Pose {
+        Pose { loc: self.loc + self.dir, dir: self.dir }
+    }
+
+    /// Turns 90 degrees clockwise as drawn on screen (rows increasing downwards)
+    pub fn turn_right(&self) -> Pose {
+        Pose { loc: self.loc, dir: self.dir.rot_90_anti() }
+    }
+
+    /// Turns 90 degrees anti-clockwise as drawn on screen (rows increasing downwards)
+    pub fn turn_left(&self) -> Pose {
+        Pose { loc: self.loc, dir: self.dir.rot_90() }
+    }
+}
+
+/// Builds a successor function for poses on `grid`: step forward onto a passable cell for
+/// `move_cost`, or turn left or right on the spot for `turn_cost`
+pub fn pose_successors<'a, T, P>(
+    grid: &'a Grid<T>,
+    passable: P,
+    move_cost: u64,
+    turn_cost: u64
+) -> impl FnMut(&Pose) -> Vec<(Pose, u64)> + 'a
+where
+    P: Fn(GridVec, &T) -> bool + 'a
+{
+    move |pose| {
+        let mut next = vec![(pose.turn_left(), turn_cost), (pose.turn_right(), turn_cost)];
+        let ahead = pose.forward();
+        if let Some(c) = grid.get(ahead.loc) && passable(ahead.loc, c) {
+            next.push((ahead, move_cost));
+        }
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::parse;
+
+    fn find(grid: &Grid<char>, target: char) -> GridVec {
+        grid.locs().find(|&loc| grid[loc] == target).unwrap()
+    }
+
+    #[test]
+    fn test_reindeer_maze() {
+        let maze = parse("\
+            ###############
+            #.......#....E#
+            #.#.###.#.###.#
+            #.....#.#...#.#
+            #.###.#####.#.#
+            #.#.#.......#.#
+            #.#.#####.###.#
+            #...........#.#
+            ###.#.#####.#.#
+            #...#.....#.#.#
+            #.#.#.###.#.#.#
+            #.....#...#.#.#
+            #.###.#.#.#.#.#
+            #S..#.....#...#
+            ###############");
+        let start = Pose { loc: find(&maze, 'S'), dir: GridVec::from([0, 1]) };
+        let end = find(&maze, 'E');
+
+        let result = dijkstra(
+            [start],
+            pose_successors(&maze, |_, &c| c != '#', 1, 1000),
+            |pose| pose.loc == end
+        ).unwrap();
+        assert_eq!(result.cost, 7036);
+
+        let tiles = result.states().into_iter().map(|p| p.loc).collect::<HashSet<_>>();
+        assert_eq!(tiles.len(), 45);
+
+        let heuristic = |pose: &Pose| ((pose.loc.i - end.i).abs() + (pose.loc.j - end.j).abs()) as u64;
+        let result = astar(
+            [start],
+            pose_successors(&maze, |_, &c| c != '#', 1, 1000),
+            heuristic,
+            |pose| pose.loc == end
+        ).unwrap();
+        assert_eq!(result.cost, 7036);
+        assert!(result.paths().iter().all(|path| path[0] == start && path.last().unwrap().loc == end));
+    }
+
+    #[test]
+    fn test_tied_paths() {
+        // Two equally cheap ways around a square, and one expensive shortcut
+        let successors = |&s: &u8| match s {
+            0 => vec![(1, 1), (2, 1), (3, 5)],
+            1 | 2 => vec![(3, 1)],
+            _ => vec![]
+        };
+        let result = dijkstra([0u8], successors, |&s| s == 3).unwrap();
+
+        let mut paths = result.paths();
+        paths.sort();
+        assert_eq!(result.cost, 2);
+        assert_eq!(paths, vec![vec![0, 1, 3], vec![0, 2, 3]]);
+        assert_eq!(result.goals().collect::<Vec<_>>(), vec![&3]);
+        assert!(dijkstra([0u8], successors, |&s| s == 9).is_none());
+    }
+}
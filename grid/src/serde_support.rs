@@ -0,0 +1,142 @@
+//! `serde` support, enabled by the `serde` feature.
+//!
+//! `GridVec` serializes as `[i, j]` and `Grid<T>` as nested arrays of rows. The `compact` and
+//! `lines` modules provide alternative `Grid` representations for use with `#[serde(with = ...)]`.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error as _;
+
+use crate::{Grid, GridVec};
+
+impl Serialize for GridVec {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        [self.i, self.j].serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for GridVec {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <[i64; 2]>::deserialize(deserializer).map(GridVec::from)
+    }
+}
+
+impl<T: Serialize> Serialize for Grid<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.rows.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Grid<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let rows = Vec::<Vec<T>>::deserialize(deserializer)?;
+        Grid::from_iter(rows.into_iter().map(|row| row.into_iter()))
+            .map_err(D::Error::custom)
+    }
+}
+
+/// Represents a `Grid<T>` as `{"rows": ..., "cols": ..., "data": [...]}`, with `data` holding the
+/// cells in row-major order
+pub mod compact {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct CompactRef<'a, T> {
+        rows: usize,
+        cols: usize,
+        data: Vec<&'a T>
+    }
+
+    #[derive(Deserialize)]
+    struct Compact<T> {
+        rows: usize,
+        cols: usize,
+        data: Vec<T>
+    }
+
+    pub fn serialize<T, S>(grid: &Grid<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer
+    {
+        CompactRef {
+            rows: grid.row_count,
+            cols: grid.col_count,
+            data: grid.rows.iter().flatten().collect()
+        }.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Grid<T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>
+    {
+        let Compact { rows, cols, data } = Compact::deserialize(deserializer)?;
+        if data.len() != rows * cols {
+            return Err(D::Error::invalid_length(data.len(), &"rows * cols cells"));
+        }
+
+        let mut data = data.into_iter();
+        Ok(Grid {
+            rows: (0..rows).map(|_| data.by_ref().take(cols).collect()).collect(),
+            row_count: rows,
+            col_count: cols
+        })
+    }
+}
+
+/// Represents a `Grid<char>` as a list of strings, one per row, just like the puzzle input
+pub mod lines {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(grid: &Grid<char>, serializer: S) -> Result<S::Ok, S::Error> {
+        grid.rows.iter()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Grid<char>, D::Error> {
+        let lines = Vec::<String>::deserialize(deserializer)?;
+        Grid::from_iter(lines.iter().map(|l| l.chars()))
+            .map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Snapshot {
+        bot: GridVec,
+        heights: Grid<u8>,
+        #[serde(with = "compact")]
+        visits: Grid<u32>,
+        #[serde(with = "lines")]
+        map: Grid<char>
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let snapshot = Snapshot {
+            bot: GridVec::from([1, -2]),
+            heights: Grid::from_iter([[0u8, 1], [2, 3]].into_iter().map(|r| r.into_iter())).unwrap(),
+            visits: Grid::from_iter([[5u32, 6, 7]].into_iter().map(|r| r.into_iter())).unwrap(),
+            map: Grid::from_iter(["#.", ".@"].into_iter().map(|l| l.chars())).unwrap()
+        };
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert_eq!(
+            json,
+            r##"{"bot":[1,-2],"heights":[[0,1],[2,3]],"visits":{"rows":1,"cols":3,"data":[5,6,7]},"map":["#.",".@"]}"##
+        );
+        assert_eq!(serde_json::from_str::<Snapshot>(&json).unwrap(), snapshot);
+    }
+
+    #[test]
+    fn test_rejects_ragged_rows() {
+        assert!(serde_json::from_str::<Grid<u8>>("[[1,2],[3]]").is_err());
+        let mut short_data = serde_json::Deserializer::from_str(r#"{"rows":2,"cols":2,"data":[1]}"#);
+        assert!(compact::deserialize::<u8, _>(&mut short_data).is_err());
+    }
+}
@@ -0,0 +1,131 @@
+use crate::{Grid, GridVec, Rect};
+
+/// Prefix sums over a grid, answering "what's the total inside this rectangle?" in constant time
+/// no matter how big the rectangle is
+#[derive(Clone, Debug)]
+pub struct SummedArea {
+    pub row_count: usize,
+    pub col_count: usize,
+    /// `sums[i][j]` is the total of every cell above and to the left of `(i, j)`, exclusive
+    sums: Vec<Vec<i64>>,
+    /// Same as `sums`, but counting non-zero cells
+    counts: Vec<Vec<u64>>
+}
+
+impl SummedArea {
+    /// Builds the table from the values of a numeric grid
+    pub fn from_grid<T: Copy + Into<i64>>(grid: &Grid<T>) -> Self {
+        let mut table = SummedArea::empty(grid.row_count, grid.col_count);
+        for i in 0..grid.row_count {
+            for j in 0..grid.col_count {
+                let value: i64 = grid.rows[i][j].into();
+                table.sums[i + 1][j + 1] = value;
+                table.counts[i + 1][j + 1] = (value != 0) as u64;
+            }
+        }
+        table.accumulate();
+
+        table
+    }
+
+    /// Builds the table from a set of points on a `row_count` x `col_count` grid, where each cell's
+    /// value is the number of points on it. Points off the grid are ignored.
+    pub fn from_points(points: impl IntoIterator<Item = GridVec>, row_count: usize, col_count: usize) -> Self {
+        let mut table = SummedArea::empty(row_count, col_count);
+        for p in points {
+            if p.i >= 0 && p.j >= 0 && (p.i as usize) < row_count && (p.j as usize) < col_count {
+                table.sums[p.i as usize + 1][p.j as usize + 1] += 1;
+            }
+        }
+        for i in 1..=row_count {
+            for j in 1..=col_count {
+                table.counts[i][j] = table.sums[i][j] as u64;
+            }
+        }
+        table.accumulate();
+
+        table
+    }
+
+    /// Total of all cell values inside `rect`. Any part of `rect` off the grid contributes nothing.
+    pub fn sum(&self, rect: Rect) -> i64 {
+        match self.corners(rect) {
+            Some([i0, j0, i1, j1]) => self.sums[i1][j1] - self.sums[i0][j1] - self.sums[i1][j0] + self.sums[i0][j0],
+            None => 0
+        }
+    }
+
+    /// Number of points inside `rect` (for tables built from points), or of non-zero cells inside
+    /// `rect` (for tables built from grids)
+    pub fn count(&self, rect: Rect) -> u64 {
+        match self.corners(rect) {
+            Some([i0, j0, i1, j1]) => self.counts[i1][j1] + self.counts[i0][j0] - self.counts[i0][j1] - self.counts[i1][j0],
+            None => 0
+        }
+    }
+
+    fn empty(row_count: usize, col_count: usize) -> Self {
+        SummedArea {
+            row_count,
+            col_count,
+            sums: vec![vec![0; col_count + 1]; row_count + 1],
+            counts: vec![vec![0; col_count + 1]; row_count + 1]
+        }
+    }
+
+    /// Turns per-cell values (stored offset by one row and col) into running totals
+    fn accumulate(&mut self) {
+        for i in 1..=self.row_count {
+            for j in 1..=self.col_count {
+                self.sums[i][j] += self.sums[i - 1][j] + self.sums[i][j - 1] - self.sums[i - 1][j - 1];
+                self.counts[i][j] += self.counts[i - 1][j] + self.counts[i][j - 1] - self.counts[i - 1][j - 1];
+            }
+        }
+    }
+
+    /// Clips `rect` to the grid, returning its corners as table indices
+    fn corners(&self, rect: Rect) -> Option<[usize; 4]> {
+        let bounds = Rect::new(GridVec::default(), GridVec::from([self.row_count, self.col_count]));
+        let rect = rect.intersect(&bounds);
+        if rect.is_empty() {
+            return None;
+        }
+
+        Some([rect.min.i as usize, rect.min.j as usize, rect.max.i as usize, rect.max.j as usize])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(min: [i64; 2], max: [i64; 2]) -> Rect {
+        Rect::new(GridVec::from(min), GridVec::from(max))
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let grid = Grid::from_iter((0..6).map(|i| (0..7).map(move |j| ((i * 7 + j) % 5) as u8))).unwrap();
+        let table = SummedArea::from_grid(&grid);
+
+        for (i0, i1) in [(0, 6), (1, 3), (2, 2), (5, 6)] {
+            for (j0, j1) in [(0, 7), (2, 5), (6, 7)] {
+                let r = rect([i0, j0], [i1, j1]);
+                let cells = grid.locs().filter(|&loc| r.contains(loc)).map(|loc| grid[loc]).collect::<Vec<_>>();
+                assert_eq!(table.sum(r), cells.iter().map(|&c| c as i64).sum::<i64>());
+                assert_eq!(table.count(r), cells.iter().filter(|&&c| c != 0).count() as u64);
+            }
+        }
+    }
+
+    #[test]
+    fn test_points() {
+        let points = [[0, 0], [0, 0], [2, 3], [4, 4], [9, 9]].map(GridVec::from);
+        let table = SummedArea::from_points(points, 5, 5);
+
+        assert_eq!(table.count(rect([0, 0], [5, 5])), 4);
+        assert_eq!(table.count(rect([-3, -3], [1, 1])), 2);
+        assert_eq!(table.count(rect([2, 3], [5, 5])), 2);
+        assert_eq!(table.count(rect([3, 0], [3, 5])), 0);
+    }
+}
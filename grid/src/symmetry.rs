@@ -0,0 +1,191 @@
+//! Scoring how close a grid or a set of points is to being mirror or rotationally symmetric.
+//!
+//! Axes and centers can fall between cells, so they're stored doubled: a mirror which maps column
+//! `j` to column `j_sum - j` sits at column `j_sum / 2`.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{Grid, GridVec};
+
+#[derive(Clone, Copy, Hash, PartialEq, Eq, Debug)]
+pub enum Symmetry {
+    /// Top-to-bottom mirror image across a horizontal line. Row `i` maps to row `i_sum - i`.
+    Horizontal { i_sum: i64 },
+    /// Left-to-right mirror image across a vertical line. Column `j` maps to column `j_sum - j`.
+    Vertical { j_sum: i64 },
+    /// Unchanged by a half turn. `loc` maps to `sum - loc`.
+    Rotational { sum: GridVec }
+}
+
+impl Symmetry {
+    /// Returns where `loc` ends up under this symmetry
+    pub fn apply(&self, loc: GridVec) -> GridVec {
+        match *self {
+            Symmetry::Horizontal { i_sum } => GridVec { i: i_sum - loc.i, j: loc.j },
+            Symmetry::Vertical { j_sum } => GridVec { i: loc.i, j: j_sum - loc.j },
+            Symmetry::Rotational { sum } => sum - loc
+        }
+    }
+}
+
+/// The best symmetry found, along with the fraction of cells (or points) it maps onto a match
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SymmetryMatch {
+    pub symmetry: Symmetry,
+    pub ratio: f64
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// Finds the horizontal mirror line which maps the most cells onto equal cells. Cells which
+    /// are mirrored off the grid count as mismatches, so only a line through the middle can score
+    /// `1.0`.
+    pub fn horizontal_symmetry(&self) -> Option<SymmetryMatch> {
+        let i_sums = 0..(2 * self.row_count as i64 - 1);
+        self.best_symmetry(i_sums.map(|i_sum| Symmetry::Horizontal { i_sum }))
+    }
+
+    /// Finds the vertical mirror line which maps the most cells onto equal cells. See
+    /// `horizontal_symmetry`.
+    pub fn vertical_symmetry(&self) -> Option<SymmetryMatch> {
+        let j_sums = 0..(2 * self.col_count as i64 - 1);
+        self.best_symmetry(j_sums.map(|j_sum| Symmetry::Vertical { j_sum }))
+    }
+
+    /// Finds the half-turn center which maps the most cells onto equal cells. Tries every center,
+    /// so this is quadratic in the number of cells.
+    pub fn rotational_symmetry(&self) -> Option<SymmetryMatch> {
+        let i_sums = 0..(2 * self.row_count as i64 - 1);
+        let j_sums = 0..(2 * self.col_count as i64 - 1);
+        let sums = i_sums.flat_map(|i| j_sums.clone().map(move |j| GridVec { i, j }));
+        self.best_symmetry(sums.map(|sum| Symmetry::Rotational { sum }))
+    }
+
+    fn best_symmetry(&self, candidates: impl Iterator<Item = Symmetry>) -> Option<SymmetryMatch> {
+        let cell_count = self.row_count * self.col_count;
+        if cell_count == 0 {
+            return None;
+        }
+
+        let mut best: Option<(Symmetry, usize)> = None;
+        for symmetry in candidates {
+            let matches = self.locs()
+                .filter(|&loc| self.get(symmetry.apply(loc)) == Some(&self[loc]))
+                .count();
+            if best.is_none_or(|(_, best_matches)| matches > best_matches) {
+                best = Some((symmetry, matches));
+            }
+        }
+
+        best.map(|(symmetry, matches)| SymmetryMatch { symmetry, ratio: matches as f64 / cell_count as f64 })
+    }
+}
+
+/// Finds the horizontal mirror line which maps the most points onto other points (or themselves)
+pub fn horizontal(points: impl IntoIterator<Item = GridVec>) -> Option<SymmetryMatch> {
+    let points = BTreeSet::from_iter(points);
+    let mut cols: BTreeMap<i64, Vec<i64>> = BTreeMap::new();
+    for p in points.iter() {
+        cols.entry(p.j).or_default().push(p.i);
+    }
+
+    let votes = pair_votes(cols.values().map(|col| col.as_slice()));
+    best_vote(votes, points.len(), |i_sum| Symmetry::Horizontal { i_sum })
+}
+
+/// Finds the vertical mirror line which maps the most points onto other points (or themselves)
+pub fn vertical(points: impl IntoIterator<Item = GridVec>) -> Option<SymmetryMatch> {
+    let points = BTreeSet::from_iter(points);
+    let mut rows: BTreeMap<i64, Vec<i64>> = BTreeMap::new();
+    for p in points.iter() {
+        rows.entry(p.i).or_default().push(p.j);
+    }
+
+    let votes = pair_votes(rows.values().map(|row| row.as_slice()));
+    best_vote(votes, points.len(), |j_sum| Symmetry::Vertical { j_sum })
+}
+
+/// Finds the half-turn center which maps the most points onto other points (or themselves)
+pub fn rotational(points: impl IntoIterator<Item = GridVec>) -> Option<SymmetryMatch> {
+    let points = Vec::from_iter(BTreeSet::from_iter(points));
+    let mut votes = BTreeMap::new();
+    for (k, &a) in points.iter().enumerate() {
+        *votes.entry(a + a).or_insert(0) += 1;
+        for &b in &points[k + 1..] {
+            *votes.entry(a + b).or_insert(0) += 2;
+        }
+    }
+
+    best_vote(votes, points.len(), |sum| Symmetry::Rotational { sum })
+}
+
+/// Every pair of positions on the same line votes for the mirror between them. A position on the
+/// mirror is its own partner, so it only gets one vote.
+fn pair_votes<'a>(lines: impl Iterator<Item = &'a [i64]>) -> BTreeMap<i64, usize> {
+    let mut votes = BTreeMap::new();
+    for line in lines {
+        for (k, &a) in line.iter().enumerate() {
+            *votes.entry(2 * a).or_insert(0) += 1;
+            for &b in &line[k + 1..] {
+                *votes.entry(a + b).or_insert(0) += 2;
+            }
+        }
+    }
+
+    votes
+}
+
+/// Picks the most voted-for symmetry, preferring the lowest key on ties
+fn best_vote<K: Copy>(
+    votes: BTreeMap<K, usize>,
+    point_count: usize,
+    symmetry: impl Fn(K) -> Symmetry
+) -> Option<SymmetryMatch> {
+    let mut best: Option<(K, usize)> = None;
+    for (key, count) in votes {
+        if best.is_none_or(|(_, best_count)| count > best_count) {
+            best = Some((key, count));
+        }
+    }
+
+    best.map(|(key, count)| SymmetryMatch { symmetry: symmetry(key), ratio: count as f64 / point_count as f64 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::parse;
+
+    #[test]
+    fn test_grid_symmetry() {
+        let tree = parse("\
+            ..#..
+            .###.
+            #####
+            ..#..");
+        assert_eq!(
+            tree.vertical_symmetry(),
+            Some(SymmetryMatch { symmetry: Symmetry::Vertical { j_sum: 4 }, ratio: 1.0 })
+        );
+        assert!(tree.horizontal_symmetry().unwrap().ratio < 1.0);
+
+        let s = parse("##.\n.#.\n.##");
+        let rotational = s.rotational_symmetry().unwrap();
+        assert_eq!(rotational.symmetry, Symmetry::Rotational { sum: GridVec::from([2, 2]) });
+        assert_eq!(rotational.ratio, 1.0);
+    }
+
+    #[test]
+    fn test_point_symmetry() {
+        // A vertical line at j = 3.5 with one stray point
+        let points = [[0, 3], [0, 4], [1, 2], [1, 5], [2, 0], [2, 7], [5, 1]].map(GridVec::from);
+
+        let best = vertical(points).unwrap();
+        assert_eq!(best.symmetry, Symmetry::Vertical { j_sum: 7 });
+        assert_eq!(best.ratio, 6.0 / 7.0);
+        assert_eq!(best.symmetry.apply(GridVec::from([1, 2])), GridVec::from([1, 5]));
+
+        assert!(horizontal(points).unwrap().ratio < 0.5);
+        assert_eq!(rotational([[0, 0], [1, 1], [2, 2]].map(GridVec::from)).unwrap().ratio, 1.0);
+        assert_eq!(vertical([]), None);
+    }
+}
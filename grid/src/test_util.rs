@@ -0,0 +1,6 @@
+use crate::Grid;
+
+/// Parses a `Grid<char>` written inline in a test, one (possibly indented) row per line
+pub fn parse(s: &str) -> Grid<char> {
+    Grid::from_iter(s.lines().map(|l| l.trim().chars())).unwrap()
+}
@@ -0,0 +1,120 @@
+use crate::{Grid, Rect};
+
+impl<T> Grid<T> {
+    /// Replaces every cell with an `H` x `W` block of cells, giving a grid `H` times taller and `W`
+    /// times wider
+    pub fn expand_cells<U, F, const W: usize, const H: usize>(&self, f: F) -> Grid<U>
+    where
+        F: Fn(&T) -> [[U; W]; H]
+    {
+        let mut rows: Vec<Vec<U>> = Vec::with_capacity(self.row_count * H);
+        for row in self.rows.iter() {
+            let first_new_row = rows.len();
+            rows.extend((0..H).map(|_| Vec::with_capacity(self.col_count * W)));
+            for cell in row.iter() {
+                for (k, block_row) in f(cell).into_iter().enumerate() {
+                    rows[first_new_row + k].extend(block_row);
+                }
+            }
+        }
+
+        Grid {
+            rows,
+            row_count: self.row_count * H,
+            col_count: self.col_count * W
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Returns a copy surrounded by `n` rows/cols of `fill` on every side
+    pub fn pad(&self, n: usize, fill: T) -> Grid<T> {
+        let col_count = self.col_count + 2*n;
+        let mut rows = vec![vec![fill.clone(); col_count]; n];
+        for row in self.rows.iter() {
+            let mut padded_row = Vec::with_capacity(col_count);
+            padded_row.extend(std::iter::repeat_n(fill.clone(), n));
+            padded_row.extend(row.iter().cloned());
+            padded_row.extend(std::iter::repeat_n(fill.clone(), n));
+            rows.push(padded_row);
+        }
+        rows.extend(std::iter::repeat_n(vec![fill; col_count], n));
+
+        Grid {
+            row_count: self.row_count + 2*n,
+            col_count,
+            rows
+        }
+    }
+
+    /// Returns a copy of the cells inside `rect`. Any part of `rect` which falls off the grid is
+    /// left out.
+    pub fn crop(&self, rect: Rect) -> Grid<T> {
+        let rect = rect.intersect(&self.bounds());
+        if rect.is_empty() {
+            return Grid { rows: vec![], row_count: 0, col_count: 0 };
+        }
+
+        let rows = (rect.min.i..rect.max.i)
+            .map(|i| self.rows[i as usize][rect.min.j as usize..rect.max.j as usize].to_vec())
+            .collect();
+
+        Grid {
+            rows,
+            row_count: rect.row_count(),
+            col_count: rect.col_count()
+        }
+    }
+
+    /// Returns a grid made of `nx` copies of this one side by side, repeated `ny` times top to bottom
+    pub fn tile(&self, nx: usize, ny: usize) -> Grid<T> {
+        let tiled_row = |row: &Vec<T>| row.iter()
+            .cycle()
+            .take(row.len() * nx)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let rows = (0..ny)
+            .flat_map(|_| self.rows.iter().map(tiled_row))
+            .collect();
+
+        Grid {
+            rows,
+            row_count: self.row_count * ny,
+            col_count: self.col_count * nx
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::parse;
+    use crate::GridVec;
+
+    #[test]
+    fn test_expand_cells() {
+        let grid = parse("#O\n@.");
+        let wide = grid.expand_cells(|c| match c {
+            'O' => [['[', ']']],
+            '@' => [['@', '.']],
+            &c => [[c, c]]
+        });
+        assert_eq!(wide, parse("##[]\n@..."));
+
+        let tall = grid.expand_cells(|&c| [[c], [c.to_ascii_lowercase()]]);
+        assert_eq!(tall, parse("#O\n#o\n@.\n@."));
+    }
+
+    #[test]
+    fn test_pad_crop_tile() {
+        let grid = parse("ab\ncd");
+
+        let padded = grid.pad(1, '.');
+        assert_eq!(padded, parse("....\n.ab.\n.cd.\n...."));
+        assert_eq!(padded.crop(Rect::new(GridVec::from([1, 1]), GridVec::from([3, 3]))), grid);
+        assert_eq!(grid.crop(Rect::new(GridVec::from([-5, 1]), GridVec::from([1, 9]))), parse("b"));
+
+        assert_eq!(grid.tile(3, 2), parse("ababab\ncdcdcd\nababab\ncdcdcd"));
+    }
+}
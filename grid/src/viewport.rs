@@ -0,0 +1,180 @@
+use std::fmt::Write;
+
+use crate::{AsChar, Grid, GridVec, Rect, ANSI_BRIGHT_BG, ANSI_RESET};
+
+const FALLBACK_TERMINAL_SIZE: (usize, usize) = (80, 24);
+
+/// Lines taken up by the column rulers plus one spare line for the prompt
+const RESERVED_LINES: usize = 3;
+
+/// A window onto part of a grid, `width` x `height` terminal characters in size (including the
+/// rulers), centered as closely as possible on `focus`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Viewport {
+    pub focus: GridVec,
+    pub width: usize,
+    pub height: usize
+}
+
+impl Viewport {
+    /// A viewport which fills the current terminal, or 80x24 if the terminal size can't be found.
+    /// The size comes from the `COLUMNS` and `LINES` environment variables, or from the terminal
+    /// itself with the `tui` feature.
+    pub fn around(focus: GridVec) -> Self {
+        let (width, height) = terminal_size().unwrap_or(FALLBACK_TERMINAL_SIZE);
+        Viewport::with_size(focus, width, height)
+    }
+
+    pub fn with_size(focus: GridVec, width: usize, height: usize) -> Self {
+        Viewport { focus, width, height }
+    }
+
+    /// Works out which cells of a `row_count` x `col_count` grid are visible
+    pub fn window(&self, row_count: usize, col_count: usize) -> Rect {
+        let gutter_width = gutter_width(row_count);
+        let visible_rows = row_count.min(self.height.saturating_sub(RESERVED_LINES));
+        let visible_cols = col_count.min(self.width.saturating_sub(gutter_width));
+
+        let first_row = window_start(self.focus.i, visible_rows, row_count);
+        let first_col = window_start(self.focus.j, visible_cols, col_count);
+
+        Rect::new(
+            GridVec::from([first_row, first_col]),
+            GridVec::from([first_row + visible_rows, first_col + visible_cols])
+        )
+    }
+}
+
+fn terminal_size() -> Option<(usize, usize)> {
+    #[cfg(feature = "tui")]
+    if let Ok((width, height)) = crossterm::terminal::size() {
+        return Some((width as usize, height as usize));
+    }
+
+    parse_terminal_size(std::env::var("COLUMNS").ok(), std::env::var("LINES").ok())
+}
+
+fn parse_terminal_size(columns: Option<String>, lines: Option<String>) -> Option<(usize, usize)> {
+    let width = columns?.trim().parse().ok()?;
+    let height = lines?.trim().parse().ok()?;
+    (width > 0 && height > 0).then_some((width, height))
+}
+
+/// Row numbers plus one column for the clipped edge marker
+fn gutter_width(row_count: usize) -> usize {
+    row_count.saturating_sub(1).to_string().len() + 1
+}
+
+fn window_start(focus: i64, visible: usize, total: usize) -> usize {
+    let max_start = (total - visible) as i64;
+    (focus - visible as i64 / 2).clamp(0, max_start) as usize
+}
+
+impl<T: AsChar> Grid<T> {
+    /// Formats the part of the grid inside `viewport`, with the focus cell highlighted. Row numbers
+    /// run down the left and column numbers across the top. Edges where the grid continues out of
+    /// view are marked with `^`, `v`, `<` and `>`.
+    pub fn format_viewport(&self, viewport: &Viewport) -> String {
+        let window = viewport.window(self.row_count, self.col_count);
+        let gutter_width = gutter_width(self.row_count);
+        let label_width = gutter_width - 1;
+
+        let mut out = String::new();
+
+        // Tens ruler, labelling every 10th column
+        let mut tens = String::new();
+        for j in window.min.j..window.max.j {
+            if tens.len() <= (j - window.min.j) as usize {
+                if j % 10 == 0 {
+                    tens.push_str(&j.to_string());
+                } else {
+                    tens.push(' ');
+                }
+            }
+        }
+        tens.truncate(window.col_count());
+        writeln!(out, "{:w$}{}", "", tens.trim_end(), w = gutter_width).unwrap();
+
+        // Units ruler, with markers for columns clipped off either side
+        let mut units = (window.min.j..window.max.j)
+            .map(|j| char::from_digit((j % 10) as u32, 10).unwrap())
+            .collect::<Vec<_>>();
+        if window.min.j > 0 && !units.is_empty() {
+            units[0] = '<';
+        }
+        if (window.max.j as usize) < self.col_count && !units.is_empty() {
+            *units.last_mut().unwrap() = '>';
+        }
+        writeln!(out, "{:w$}{}", "", units.iter().collect::<String>(), w = gutter_width).unwrap();
+
+        for i in window.min.i..window.max.i {
+            let marker = if i == window.min.i && window.min.i > 0 {
+                '^'
+            } else if i == window.max.i - 1 && (window.max.i as usize) < self.row_count {
+                'v'
+            } else {
+                ' '
+            };
+            write!(out, "{:>w$}{}", i, marker, w = label_width).unwrap();
+
+            for j in window.min.j..window.max.j {
+                let loc = GridVec { i, j };
+                if loc == viewport.focus {
+                    write!(out, "{}{}{}", ANSI_BRIGHT_BG, self[loc].as_char(), ANSI_RESET).unwrap();
+                } else {
+                    out.push(self[loc].as_char());
+                }
+            }
+            out.push('\n');
+        }
+
+        out
+    }
+
+    /// Prints as much of the grid as fits in the terminal, centered on `focus`
+    pub fn render_viewport(&self, focus: GridVec) {
+        print!("{}", self.format_viewport(&Viewport::around(focus)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered_grid(row_count: usize, col_count: usize) -> Grid<char> {
+        Grid::from_iter((0..row_count).map(|_| (0..col_count).map(|j| if j % 5 == 0 { '#' } else { '.' }))).unwrap()
+    }
+
+    #[test]
+    fn test_window_clamps_to_edges() {
+        let viewport = Viewport { focus: GridVec::from([0, 29]), width: 13, height: 8 };
+        assert_eq!(
+            viewport.window(20, 30),
+            Rect::new(GridVec::from([0, 20]), GridVec::from([5, 30]))
+        );
+
+        let small = Viewport { focus: GridVec::from([1, 1]), width: 80, height: 24 };
+        assert_eq!(small.window(3, 4), Rect::new(GridVec::from([0, 0]), GridVec::from([3, 4])));
+    }
+
+    #[test]
+    fn test_parse_terminal_size() {
+        assert_eq!(parse_terminal_size(Some("120".into()), Some("40\n".into())), Some((120, 40)));
+        assert_eq!(parse_terminal_size(Some("120".into()), None), None);
+        assert_eq!(parse_terminal_size(Some("wide".into()), Some("40".into())), None);
+        assert_eq!(parse_terminal_size(Some("0".into()), Some("40".into())), None);
+    }
+
+    #[test]
+    fn test_format_viewport() {
+        let grid = numbered_grid(12, 30);
+        let viewport = Viewport { focus: GridVec::from([6, 14]), width: 11, height: 6 };
+
+        let expected = format!(
+            "   10\n   <123456>\n 5^#....#..\n 6 #...{}.{}#..\n 7v#....#..\n",
+            ANSI_BRIGHT_BG,
+            ANSI_RESET
+        );
+        assert_eq!(grid.format_viewport(&viewport), expected);
+    }
+}
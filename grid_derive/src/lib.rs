@@ -0,0 +1,108 @@
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitChar};
+
+/// Derives conversions between a fieldless enum and the characters used to draw it on a puzzle
+/// map. Every variant needs a `#[cell('c')]` attribute giving its character.
+///
+/// Generates `TryFrom<char>` (failing with `grid::Error::InvalidCell`), `From<Enum> for char`,
+/// `Display` and `grid::AsChar`, so the enum can be used with `Grid::parse` and the renderers. The
+/// same conversions are also generated for the character's code point as a `u32`, which is what
+/// `grid::frames` stores.
+#[proc_macro_derive(GridCell, attributes(cell))]
+pub fn derive_grid_cell(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into()
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new(Span::call_site(), "GridCell can only be derived for enums"));
+    };
+
+    let mut idents = vec![];
+    let mut chars = vec![];
+    for variant in data.variants.iter() {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new_spanned(variant, "GridCell variants can't have fields"));
+        }
+
+        let mut cell_char = None;
+        for attr in variant.attrs.iter().filter(|a| a.path().is_ident("cell")) {
+            if cell_char.is_some() {
+                return Err(syn::Error::new_spanned(attr, "duplicate #[cell(...)] attribute"));
+            }
+            cell_char = Some(attr.parse_args::<LitChar>()?);
+        }
+        let Some(cell_char) = cell_char else {
+            return Err(syn::Error::new_spanned(variant, "missing #[cell('c')] attribute"));
+        };
+
+        if let Some(prev) = chars.iter().find(|c: &&LitChar| c.value() == cell_char.value()) {
+            let mut err = syn::Error::new_spanned(&cell_char, "character is already used by another variant");
+            err.combine(syn::Error::new_spanned(prev, "first used here"));
+            return Err(err);
+        }
+
+        idents.push(&variant.ident);
+        chars.push(cell_char);
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::core::convert::TryFrom<char> for #name #ty_generics #where_clause {
+            type Error = ::grid::Error;
+
+            fn try_from(c: char) -> ::core::result::Result<Self, Self::Error> {
+                match c {
+                    #( #chars => Ok(#name::#idents), )*
+                    _ => Err(::grid::Error::InvalidCell(c))
+                }
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<#name #ty_generics> for char #where_clause {
+            fn from(cell: #name #ty_generics) -> char {
+                match cell {
+                    #( #name::#idents => #chars, )*
+                }
+            }
+        }
+
+        impl #impl_generics ::core::convert::TryFrom<u32> for #name #ty_generics #where_clause {
+            type Error = ::grid::Error;
+
+            fn try_from(code: u32) -> ::core::result::Result<Self, Self::Error> {
+                let c = char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER);
+                Self::try_from(c)
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<#name #ty_generics> for u32 #where_clause {
+            fn from(cell: #name #ty_generics) -> u32 {
+                char::from(cell) as u32
+            }
+        }
+
+        impl #impl_generics ::grid::AsChar for #name #ty_generics #where_clause {
+            fn as_char(&self) -> char {
+                match self {
+                    #( #name::#idents => #chars, )*
+                }
+            }
+        }
+
+        impl #impl_generics ::core::fmt::Display for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                use ::core::fmt::Write;
+                f.write_char(::grid::AsChar::as_char(self))
+            }
+        }
+    })
+}
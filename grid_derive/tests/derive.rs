@@ -0,0 +1,74 @@
+use grid::{AsChar, Error, Grid, GridCell, GridVec};
+use grid::frames::{FrameReader, FrameWriter};
+
+#[derive(GridCell, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum Tile {
+    #[cell('.')]
+    Empty,
+    #[cell('#')]
+    Wall,
+    #[cell('@')]
+    Robot
+}
+
+#[test]
+fn test_char_conversions() {
+    assert_eq!(Tile::try_from('#').unwrap(), Tile::Wall);
+    assert!(matches!(Tile::try_from('x'), Err(Error::InvalidCell('x'))));
+    assert_eq!(char::from(Tile::Robot), '@');
+    assert_eq!(Tile::Empty.as_char(), '.');
+    assert_eq!(Tile::Wall.to_string(), "#");
+
+    assert_eq!(u32::from(Tile::Robot), '@' as u32);
+    assert_eq!(Tile::try_from('.' as u32).unwrap(), Tile::Empty);
+    assert!(matches!(Tile::try_from(0xd800), Err(Error::InvalidCell(char::REPLACEMENT_CHARACTER))));
+}
+
+#[test]
+fn test_parse_round_trips() {
+    let input = "#####\n#.@.#\n#####";
+    let grid = Grid::<Tile>::parse(input).unwrap();
+    assert_eq!(grid[GridVec::from([1, 2])], Tile::Robot);
+    assert_eq!(grid.rows.iter().flatten().filter(|&&t| t == Tile::Wall).count(), 12);
+
+    let rendered = grid.rows.iter()
+        .map(|row| row.iter().map(|&t| char::from(t)).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert_eq!(rendered, input);
+
+    assert!(matches!(Grid::<Tile>::parse("#.\n#x"), Err(Error::InvalidCell('x'))));
+}
+
+#[derive(GridCell, Clone, Copy, PartialEq, Eq, Debug)]
+enum Space {
+    #[cell(' ')]
+    Blank,
+    #[cell('#')]
+    Wall
+}
+
+#[test]
+fn test_parse_keeps_edge_whitespace() {
+    let grid = Grid::<Space>::parse(" # \n#  ").unwrap();
+    assert_eq!(grid.col_count, 3);
+    assert_eq!(grid.rows[0], vec![Space::Blank, Space::Wall, Space::Blank]);
+    assert_eq!(grid.rows[1], vec![Space::Wall, Space::Blank, Space::Blank]);
+}
+
+#[test]
+fn test_record_frames() {
+    let mut grid = Grid::<Tile>::parse("#####\n#@..#\n#####").unwrap();
+    let mut writer = FrameWriter::new(grid.row_count, grid.col_count);
+    let mut frames = vec![];
+    for j in 1..4 {
+        grid[GridVec::from([1, j])] = Tile::Robot;
+        writer.push(&grid).unwrap();
+        frames.push(grid.clone());
+        grid[GridVec::from([1, j])] = Tile::Empty;
+    }
+
+    let data = writer.finish();
+    let reader = FrameReader::<Tile>::new(&data).unwrap();
+    assert_eq!(reader.frames().collect::<Result<Vec<_>, _>>().unwrap(), frames);
+}